@@ -0,0 +1,288 @@
+// A circular doubly linked list, with its nodes stored in a `Vec` and linked by index.
+//
+// Nodes are referred to by the `NodeId` returned when they're inserted, which stays valid until
+// that node is removed, so inserting, removing and moving a node are all O(1). The slots of
+// removed nodes are reused by later insertions.
+use std::ops::{Index, IndexMut};
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct NodeId(usize);
+
+#[derive(Clone, Debug)]
+struct Node<T> {
+    // `None` once the node has been removed.
+    value: Option<T>,
+    prev: usize,
+    next: usize,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct CircularList<T> {
+    nodes: Vec<Node<T>>,
+    free: Vec<usize>,
+    head: Option<usize>,
+    len: usize,
+}
+
+impl<T> CircularList<T> {
+    pub fn new() -> Self {
+        Self::with_capacity(0)
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        CircularList {
+            nodes: Vec::with_capacity(capacity),
+            free: Vec::new(),
+            head: None,
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    // The node that iteration starts from by default: the first one added that's still there,
+    // or its successor.
+    pub fn head(&self) -> Option<NodeId> {
+        self.head.map(NodeId)
+    }
+
+    // Store `value` in a free slot, linked to itself.
+    fn allocate(&mut self, value: T) -> usize {
+        self.len += 1;
+        match self.free.pop() {
+            Some(idx) => {
+                self.nodes[idx] = Node {
+                    value: Some(value),
+                    prev: idx,
+                    next: idx,
+                };
+                idx
+            }
+            None => {
+                let idx = self.nodes.len();
+                self.nodes.push(Node {
+                    value: Some(value),
+                    prev: idx,
+                    next: idx,
+                });
+                idx
+            }
+        }
+    }
+
+    fn node(&self, id: NodeId) -> &Node<T> {
+        let node = &self.nodes[id.0];
+        assert!(node.value.is_some(), "Node {} has been removed.", id.0);
+        node
+    }
+
+    // Link the unlinked node `idx` in between `prev` and its successor.
+    fn link_after(&mut self, prev: usize, idx: usize) {
+        let next = self.nodes[prev].next;
+        self.nodes[idx].prev = prev;
+        self.nodes[idx].next = next;
+        self.nodes[prev].next = idx;
+        self.nodes[next].prev = idx;
+    }
+
+    // Join the neighbours of `idx` to each other, leaving `idx` linked to itself.
+    fn unlink(&mut self, idx: usize) {
+        let Node { prev, next, .. } = self.nodes[idx];
+        self.nodes[prev].next = next;
+        self.nodes[next].prev = prev;
+        self.nodes[idx].prev = idx;
+        self.nodes[idx].next = idx;
+        if self.head == Some(idx) {
+            self.head = (next != idx).then_some(next);
+        }
+    }
+
+    // Add `value` just before the head, which is the end of the list when iterating from the
+    // head. If the list is empty, the new node becomes the head.
+    pub fn push_back(&mut self, value: T) -> NodeId {
+        let idx = self.allocate(value);
+        match self.head {
+            Some(head) => self.link_after(self.nodes[head].prev, idx),
+            None => self.head = Some(idx),
+        }
+        NodeId(idx)
+    }
+
+    pub fn insert_after(&mut self, id: NodeId, value: T) -> NodeId {
+        self.node(id);
+        let idx = self.allocate(value);
+        self.link_after(id.0, idx);
+        NodeId(idx)
+    }
+
+    pub fn insert_before(&mut self, id: NodeId, value: T) -> NodeId {
+        let prev = self.node(id).prev;
+        let idx = self.allocate(value);
+        self.link_after(prev, idx);
+        NodeId(idx)
+    }
+
+    // Take the node out of the list, returning its value. Its `NodeId` is then invalid.
+    pub fn remove(&mut self, id: NodeId) -> T {
+        self.node(id);
+        self.unlink(id.0);
+        self.free.push(id.0);
+        self.len -= 1;
+        self.nodes[id.0].value.take().unwrap()
+    }
+
+    // Move the node `id` so that it comes straight after `target`.
+    pub fn move_after(&mut self, id: NodeId, target: NodeId) {
+        self.node(target);
+        if id == target {
+            return;
+        }
+        let was_head = self.head == Some(id.0);
+        self.unlink(id.0);
+        self.link_after(target.0, id.0);
+        if was_head {
+            self.head = Some(id.0);
+        }
+    }
+
+    pub fn next(&self, id: NodeId) -> NodeId {
+        NodeId(self.node(id).next)
+    }
+
+    pub fn prev(&self, id: NodeId) -> NodeId {
+        NodeId(self.node(id).prev)
+    }
+
+    // The node `steps` places clockwise from `id`, or anticlockwise if `steps` is negative. This
+    // takes time proportional to `steps`, reduced modulo the length of the list.
+    pub fn offset(&self, id: NodeId, steps: isize) -> NodeId {
+        self.node(id);
+        let steps = steps.rem_euclid(self.len as isize) as usize;
+        let mut idx = id.0;
+        if steps <= self.len / 2 {
+            for _ in 0..steps {
+                idx = self.nodes[idx].next;
+            }
+        } else {
+            for _ in steps..self.len {
+                idx = self.nodes[idx].prev;
+            }
+        }
+        NodeId(idx)
+    }
+
+    // Every node once, going clockwise from `id`.
+    pub fn ids_from(&self, id: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        self.node(id);
+        let mut idx = id.0;
+        (0..self.len).map(move |_| {
+            let current = idx;
+            idx = self.nodes[idx].next;
+            NodeId(current)
+        })
+    }
+
+    // Every value once, going clockwise from `id`.
+    pub fn iter_from(&self, id: NodeId) -> impl Iterator<Item = &T> + '_ {
+        self.ids_from(id).map(move |id| &self[id])
+    }
+
+    // Every value once, going clockwise from the head.
+    pub fn iter(&self) -> impl Iterator<Item = &T> + '_ {
+        self.head
+            .into_iter()
+            .flat_map(move |head| self.iter_from(NodeId(head)))
+    }
+}
+
+impl<T> Index<NodeId> for CircularList<T> {
+    type Output = T;
+
+    fn index(&self, id: NodeId) -> &T {
+        self.node(id).value.as_ref().unwrap()
+    }
+}
+
+impl<T> IndexMut<NodeId> for CircularList<T> {
+    fn index_mut(&mut self, id: NodeId) -> &mut T {
+        self.node(id);
+        self.nodes[id.0].value.as_mut().unwrap()
+    }
+}
+
+impl<T> FromIterator<T> for CircularList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = CircularList::new();
+        for value in iter {
+            list.push_back(value);
+        }
+        list
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CircularList;
+
+    fn values(list: &CircularList<u32>) -> Vec<u32> {
+        list.iter().copied().collect()
+    }
+
+    #[test]
+    fn check_insert_and_remove() {
+        let mut list = CircularList::new();
+        assert_eq!(list.head(), None);
+        let one = list.push_back(1);
+        let three = list.push_back(3);
+        list.insert_after(one, 2);
+        let zero = list.insert_before(one, 0);
+        assert_eq!(values(&list), vec![1, 2, 3, 0]);
+        assert_eq!(list.len(), 4);
+
+        assert_eq!(list.remove(one), 1);
+        assert_eq!(list.head(), Some(list.next(zero)));
+        assert_eq!(values(&list), vec![2, 3, 0]);
+        list[three] = 30;
+        assert_eq!(
+            list.iter_from(three).copied().collect::<Vec<_>>(),
+            vec![30, 0, 2]
+        );
+
+        // The removed node's slot is reused.
+        let four = list.insert_after(zero, 4);
+        assert_eq!(list.prev(four), zero);
+        assert_eq!(values(&list), vec![2, 30, 0, 4]);
+        for id in list.ids_from(zero).collect::<Vec<_>>() {
+            list.remove(id);
+        }
+        assert!(list.is_empty() && list.head().is_none());
+    }
+
+    #[test]
+    fn check_navigation_and_moves() {
+        let mut list: CircularList<u32> = (0..10).collect();
+        let zero = list.head().unwrap();
+        assert_eq!(list[list.offset(zero, 3)], 3);
+        assert_eq!(list[list.offset(zero, -3)], 7);
+        assert_eq!(list[list.offset(zero, 23)], 3);
+        assert_eq!(list[list.offset(zero, -7)], 3);
+
+        // Move 1, 2 and 3 after 7, like a move in 2020 day 23.
+        let seven = list.offset(zero, 7);
+        let mut target = seven;
+        for _ in 0..3 {
+            let picked = list.next(zero);
+            list.move_after(picked, target);
+            target = picked;
+        }
+        assert_eq!(values(&list), vec![0, 4, 5, 6, 7, 1, 2, 3, 8, 9]);
+        list.move_after(zero, seven);
+        assert_eq!(values(&list), vec![0, 1, 2, 3, 8, 9, 4, 5, 6, 7]);
+    }
+}
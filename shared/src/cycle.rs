@@ -0,0 +1,157 @@
+// Finding where a process starts repeating itself, and using that to jump far into the future.
+//
+// Whether two states count as the same is decided by a `key`. Using the whole state finds exact
+// cycles. Using a normalised form, such as a pattern with its position removed, finds states that
+// repeat while drifting, and `extrapolate` carries the drift forwards.
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+// The first item whose key has been seen before, with its index and the index of the earlier
+// item with the same key.
+pub fn first_repeat<T, K: Hash + Eq>(
+    items: impl IntoIterator<Item = T>,
+    mut key: impl FnMut(&T) -> K,
+) -> Option<(usize, usize, T)> {
+    let mut seen = HashMap::new();
+    for (idx, item) in items.into_iter().enumerate() {
+        match seen.entry(key(&item)) {
+            Entry::Occupied(entry) => return Some((*entry.get(), idx, item)),
+            Entry::Vacant(entry) => {
+                entry.insert(idx);
+            }
+        }
+    }
+    None
+}
+
+// The states of a process from step 0 until it has gone once around its cycle, and then once
+// more. States `start + length..` repeat states `start..`, as far as their keys are concerned.
+#[derive(Clone, Debug)]
+pub struct Cycle<S> {
+    states: Vec<S>,
+    start: usize,
+    length: usize,
+}
+
+// Run `step` from `initial` until a state's key matches an earlier one. This doesn't return if
+// the keys never repeat.
+pub fn find_cycle<S, K: Hash + Eq>(
+    initial: S,
+    mut step: impl FnMut(&S) -> S,
+    mut key: impl FnMut(&S) -> K,
+) -> Cycle<S> {
+    let mut states = vec![initial];
+    let mut seen = HashMap::new();
+    let start = loop {
+        let idx = states.len() - 1;
+        match seen.entry(key(&states[idx])) {
+            Entry::Occupied(entry) => break *entry.get(),
+            Entry::Vacant(entry) => {
+                entry.insert(idx);
+            }
+        }
+        let next = step(&states[idx]);
+        states.push(next);
+    };
+    let length = states.len() - 1 - start;
+
+    // One more lap, so that `extrapolate` can see how much each quantity changes per lap.
+    for _ in 1..length {
+        let next = step(states.last().unwrap());
+        states.push(next);
+    }
+    Cycle {
+        states,
+        start,
+        length,
+    }
+}
+
+impl<S> Cycle<S> {
+    // The first step in the cycle.
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    pub fn length(&self) -> usize {
+        self.length
+    }
+
+    // Every state that was simulated, starting with the initial state.
+    pub fn states(&self) -> &[S] {
+        &self.states
+    }
+
+    // The state at step `n`, or one with the same key once `n` is past the states simulated.
+    pub fn state_at(&self, n: usize) -> &S {
+        &self.states[self.simulated_step(n).0]
+    }
+
+    // The step with the same key as step `n` that was simulated, and how many laps of the cycle
+    // it is behind step `n`.
+    fn simulated_step(&self, n: usize) -> (usize, usize) {
+        if n < self.states.len() {
+            (n, 0)
+        } else {
+            let laps = (n - self.start) / self.length;
+            (self.start + (n - self.start) % self.length, laps)
+        }
+    }
+
+    // The value of `quantity` at step `n`, assuming that it changes by the same amount each time
+    // around the cycle. For exact cycles the change is zero; for a drifting pattern it's how far
+    // the quantity moves in one lap.
+    pub fn extrapolate(&self, n: usize, mut quantity: impl FnMut(&S) -> i64) -> i64 {
+        let (step, laps) = self.simulated_step(n);
+        let value = quantity(&self.states[step]);
+        if laps == 0 {
+            return value;
+        }
+        let change_per_lap = quantity(&self.states[step + self.length]) - value;
+        value + laps as i64 * change_per_lap
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{find_cycle, first_repeat};
+
+    #[test]
+    fn check_first_repeat() {
+        assert_eq!(first_repeat([3, 1, 4, 1, 5], |&n| n), Some((1, 3, 1)));
+        assert_eq!(
+            first_repeat(["ab", "cd", "Ab"], |s| s.to_lowercase()),
+            Some((0, 2, "Ab"))
+        );
+        assert_eq!(first_repeat(0..10, |&n| n), None);
+    }
+
+    #[test]
+    fn check_exact_cycle() {
+        // 1, 2, 4, 8, 16, 13, 7, 14, 9, 18, 17, 15, 11, 3, 6, 12, 5, 10, 1, ... (mod 19)
+        let cycle = find_cycle(1_u32, |&n| n * 2 % 19, |&n| n);
+        assert_eq!((cycle.start(), cycle.length()), (0, 18));
+        assert_eq!(*cycle.state_at(1_000_000), 2_u32.pow(1_000_000 % 18) % 19);
+        assert_eq!(cycle.extrapolate(1_000_000, |&n| n as i64), 17);
+
+        let cycle = find_cycle(0_u32, |&n| if n < 5 { n + 1 } else { 3 }, |&n| n);
+        assert_eq!((cycle.start(), cycle.length()), (3, 3));
+        assert_eq!(*cycle.state_at(2), 2);
+        assert_eq!(*cycle.state_at(100), 4);
+    }
+
+    #[test]
+    fn check_drifting_cycle() {
+        // A pair of positions that settles into moving right by 3 every 2 steps.
+        let step = |&(a, b): &(i64, i64)| if b - a > 2 { (a, b - 1) } else { (b, a + 3) };
+        let cycle = find_cycle((0, 10), step, |&(a, b)| b - a);
+        assert_eq!(cycle.length(), 2);
+        let mut state = (0, 10);
+        for _ in 0..1_001 {
+            state = step(&state);
+        }
+        assert_eq!(cycle.extrapolate(1_001, |&(a, _)| a), state.0);
+        assert_eq!(cycle.extrapolate(1_001, |&(a, b)| a + b), state.0 + state.1);
+    }
+}
@@ -0,0 +1,122 @@
+// Differential testing: run several implementations of a day against the same input and check
+// that they all give the same answers.
+//
+// Run it with `cargo run diff-impl [DAY]` from a year's directory.
+use std::fmt::Write;
+use std::time::{Duration, Instant};
+
+pub type DayFunction = fn(&[Vec<String>]) -> (String, String);
+
+pub struct Run {
+    pub name: &'static str,
+    pub answers: (String, String),
+    pub elapsed: Duration,
+}
+
+pub struct Comparison {
+    pub input_name: String,
+    pub runs: Vec<Run>,
+}
+
+impl Comparison {
+    pub fn agrees(&self) -> bool {
+        self.runs
+            .windows(2)
+            .all(|pair| pair[0].answers == pair[1].answers)
+    }
+}
+
+pub fn compare(
+    input_name: &str,
+    input_lines: &[Vec<String>],
+    implementations: &[(&'static str, DayFunction)],
+) -> Comparison {
+    let runs = implementations
+        .iter()
+        .map(|&(name, day_function)| {
+            let start_time = Instant::now();
+            let answers = day_function(input_lines);
+            Run {
+                name,
+                answers,
+                elapsed: start_time.elapsed(),
+            }
+        })
+        .collect();
+    Comparison {
+        input_name: input_name.to_string(),
+        runs,
+    }
+}
+
+// Render the timings of every run, and the answers of every run that disagrees.
+pub fn report(comparisons: &[Comparison]) -> String {
+    let mut report = String::new();
+    for comparison in comparisons {
+        let agrees = comparison.agrees();
+        let verdict = if agrees { "agree" } else { "DISAGREE" };
+        writeln!(report, "{}: {}", comparison.input_name, verdict).unwrap();
+        for run in &comparison.runs {
+            write!(
+                report,
+                "    {:<20} {:>12.3}ms",
+                run.name,
+                run.elapsed.as_secs_f64() * 1000.0
+            )
+            .unwrap();
+            if !agrees {
+                write!(
+                    report,
+                    "  Part 1: {}  Part 2: {}",
+                    run.answers.0, run.answers.1
+                )
+                .unwrap();
+            }
+            writeln!(report).unwrap();
+        }
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compare, report};
+
+    fn count_lines(input_lines: &[Vec<String>]) -> (String, String) {
+        (input_lines[0].len().to_string(), "0".to_string())
+    }
+
+    fn count_chars(input_lines: &[Vec<String>]) -> (String, String) {
+        let chars: usize = input_lines[0].iter().map(|line| line.len()).sum();
+        (chars.to_string(), "0".to_string())
+    }
+
+    #[test]
+    fn check_agreeing_implementations() {
+        let input_lines = vec![vec!["a".to_string(), "b".to_string()]];
+        let comparison = compare(
+            "example",
+            &input_lines,
+            &[("lines", count_lines), ("chars", count_chars)],
+        );
+        assert!(comparison.agrees());
+        let report = report(&[comparison]);
+        assert!(report.starts_with("example: agree\n"));
+        assert!(!report.contains("Part 1"));
+    }
+
+    #[test]
+    fn check_disagreeing_implementations() {
+        let input_lines = vec![vec!["ab".to_string()]];
+        let comparison = compare(
+            "example",
+            &input_lines,
+            &[("lines", count_lines), ("chars", count_chars)],
+        );
+        assert!(!comparison.agrees());
+        let report = report(&[comparison]);
+        assert!(report.starts_with("example: DISAGREE\n"));
+        assert!(report.contains("Part 1: 1  Part 2: 0"));
+        assert!(report.contains("Part 1: 2  Part 2: 0"));
+    }
+}
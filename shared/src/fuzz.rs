@@ -0,0 +1,144 @@
+// Random and mutated puzzle inputs, for checking that parsers and solvers fail gracefully.
+//
+// Everything is generated from a fixed seed so that a failing input can be reproduced. For
+// coverage-guided fuzzing of a single parser, see the `fuzz` directory of each year.
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use std::fmt::Debug;
+use std::panic;
+use std::str::FromStr;
+
+pub const DEFAULT_SEED: u64 = 25;
+pub const DEFAULT_CASES: usize = 1000;
+const MAX_MUTATIONS: usize = 4;
+
+// Characters that are likely to confuse a parser: digits, signs, separators and whitespace,
+// plus some non-ASCII characters that take up more than one byte.
+const INTERESTING_CHARS: &[char] = &[
+    '0', '1', '9', '-', '+', '#', '.', ',', ':', ';', '|', '@', 'x', '<', '>', '=', ' ', '\t', 'é',
+    '€', '🎄',
+];
+const INTERESTING_NUMBERS: &[&str] = &["0", "-1", "4294967296", "99999999999999999999"];
+
+// Produce `count` inputs, each made by applying a few random mutations to one of `samples`.
+pub fn mutations(samples: &[&str], seed: u64, count: usize) -> Vec<String> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    (0..count)
+        .map(|_| {
+            let mut chars: Vec<char> = samples.choose(&mut rng).unwrap().chars().collect();
+            for _ in 0..rng.gen_range(1..=MAX_MUTATIONS) {
+                mutate(&mut chars, &mut rng);
+            }
+            chars.into_iter().collect()
+        })
+        .collect()
+}
+
+fn mutate(chars: &mut Vec<char>, rng: &mut StdRng) {
+    let len = chars.len();
+    match rng.gen_range(0..6) {
+        // Insert a character.
+        0 => chars.insert(
+            rng.gen_range(0..=len),
+            *INTERESTING_CHARS.choose(rng).unwrap(),
+        ),
+        // Replace a character.
+        1 if len > 0 => chars[rng.gen_range(0..len)] = *INTERESTING_CHARS.choose(rng).unwrap(),
+        // Delete a run of characters.
+        2 if len > 0 => {
+            let start = rng.gen_range(0..len);
+            let end = rng.gen_range(start..=len);
+            chars.drain(start..end);
+        }
+        // Duplicate a run of characters.
+        3 if len > 0 => {
+            let start = rng.gen_range(0..len);
+            let end = rng.gen_range(start..=len);
+            let run: Vec<char> = chars[start..end].to_vec();
+            let at = rng.gen_range(0..=chars.len());
+            chars.splice(at..at, run);
+        }
+        // Truncate.
+        4 => chars.truncate(rng.gen_range(0..=len)),
+        // Insert a number that's out of range or has the wrong sign.
+        _ => {
+            let at = rng.gen_range(0..=len);
+            let number = INTERESTING_NUMBERS.choose(rng).unwrap().chars();
+            chars.splice(at..at, number);
+        }
+    }
+}
+
+// Produce `count` strings of up to `max_len` characters drawn from `alphabet`.
+pub fn random_strings(alphabet: &str, max_len: usize, seed: u64, count: usize) -> Vec<String> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let alphabet: Vec<char> = alphabet.chars().collect();
+    (0..count)
+        .map(|_| {
+            (0..rng.gen_range(0..=max_len))
+                .map(|_| *alphabet.choose(&mut rng).unwrap())
+                .collect()
+        })
+        .collect()
+}
+
+// Check that `check` passes for every input, reporting the first input that fails or panics.
+pub fn assert_for_all<I, F>(inputs: I, check: F)
+where
+    I: IntoIterator,
+    I::Item: Debug + panic::RefUnwindSafe,
+    F: Fn(&I::Item) -> bool + panic::RefUnwindSafe,
+{
+    for input in inputs {
+        match panic::catch_unwind(|| check(&input)) {
+            Ok(true) => {}
+            Ok(false) => panic!("Check failed for input {:?}", input),
+            Err(_) => panic!("Check panicked for input {:?}", input),
+        }
+    }
+}
+
+// Check that `T::from_str` returns (rather than panics) for mutations of every sample, and that
+// it accepts the unmutated samples.
+pub fn assert_parser_never_panics<T: FromStr>(samples: &[&str]) {
+    assert_for_all(samples, |sample| sample.parse::<T>().is_ok());
+    assert_for_all(mutations(samples, DEFAULT_SEED, DEFAULT_CASES), |input| {
+        let _ = input.parse::<T>();
+        true
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{assert_for_all, assert_parser_never_panics, mutations, random_strings};
+
+    #[test]
+    fn check_mutations_are_reproducible() {
+        let samples = ["1-3 a: abcde", "Card 1: 41 48 | 83 86"];
+        assert_eq!(mutations(&samples, 1, 50), mutations(&samples, 1, 50));
+        assert_ne!(mutations(&samples, 1, 50), mutations(&samples, 2, 50));
+    }
+
+    #[test]
+    fn check_random_strings_use_alphabet() {
+        let strings = random_strings("aB", 10, 1, 100);
+        assert_for_all(strings, |s| {
+            s.len() <= 10 && s.chars().all(|c| c == 'a' || c == 'B')
+        });
+    }
+
+    #[test]
+    fn check_parser_never_panics() {
+        assert_parser_never_panics::<i64>(&["12", "-7"]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Check panicked")]
+    fn check_panicking_parser_is_reported() {
+        assert_for_all(mutations(&["12"], 1, 100), |s| {
+            s.parse::<u8>().unwrap();
+            true
+        });
+    }
+}
@@ -0,0 +1,570 @@
+// Graph algorithms over nodes of any hashable type.
+//
+// The searches take the graph as a `successors` closure, so they work equally well on an explicit
+// `DiGraph`, a map of adjacency lists or a graph that's generated as it's explored (a grid, a
+// puzzle state, ...). Weighted searches expect the closure to yield `(node, cost)` pairs.
+use std::cmp::Reverse;
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, BinaryHeap, HashMap, HashSet, VecDeque};
+use std::fmt::{self, Display, Write};
+use std::hash::Hash;
+use std::ops::Add;
+
+// A directed graph with weighted edges, stored as adjacency lists. Nodes are kept in order, so
+// iterating over them and exporting them is deterministic.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DiGraph<N> {
+    edges: BTreeMap<N, Vec<(N, u64)>>,
+}
+
+impl<N> Default for DiGraph<N> {
+    fn default() -> Self {
+        DiGraph {
+            edges: BTreeMap::new(),
+        }
+    }
+}
+
+impl<N: Clone + Ord + Hash> DiGraph<N> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Build a graph from unweighted `(from, to)` edges.
+    pub fn from_edges(edges: impl IntoIterator<Item = (N, N)>) -> Self {
+        let mut graph = Self::new();
+        for (from, to) in edges {
+            graph.add_edge(from, to);
+        }
+        graph
+    }
+
+    pub fn add_node(&mut self, node: N) {
+        self.edges.entry(node).or_default();
+    }
+
+    // Add an edge of weight 1, adding either node if it's new.
+    pub fn add_edge(&mut self, from: N, to: N) {
+        self.add_weighted_edge(from, to, 1);
+    }
+
+    pub fn add_weighted_edge(&mut self, from: N, to: N, weight: u64) {
+        self.add_node(to.clone());
+        self.edges.entry(from).or_default().push((to, weight));
+    }
+
+    pub fn contains_node(&self, node: &N) -> bool {
+        self.edges.contains_key(node)
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.edges.len()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.edges.values().map(Vec::len).sum()
+    }
+
+    pub fn nodes(&self) -> impl Iterator<Item = &N> {
+        self.edges.keys()
+    }
+
+    // The nodes at the end of the edges leaving `node`, in the order the edges were added.
+    pub fn successors(&self, node: &N) -> impl Iterator<Item = N> + '_ {
+        self.weighted_successors(node).map(|(next, _)| next)
+    }
+
+    pub fn weighted_successors(&self, node: &N) -> impl Iterator<Item = (N, u64)> + '_ {
+        self.edges.get(node).into_iter().flatten().cloned()
+    }
+
+    // The nodes in dependency order, choosing the smallest available node at each step. Returns
+    // `None` if the graph has a cycle.
+    pub fn topological_sort(&self) -> Option<Vec<N>> {
+        topological_sort(self.nodes().cloned(), |node| self.successors(node))
+    }
+
+    pub fn strongly_connected_components(&self) -> Vec<Vec<N>> {
+        strongly_connected_components(self.nodes().cloned(), |node| self.successors(node))
+    }
+}
+
+impl<N: Clone + Ord + Hash + Display> DiGraph<N> {
+    // Render the graph in Graphviz's DOT language, e.g. for `dot -Tsvg`. Edges with a weight
+    // other than 1 are labelled with it.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph {\n");
+        for (node, edges) in &self.edges {
+            writeln!(dot, "    \"{}\";", node).unwrap();
+            for (next, weight) in edges {
+                write!(dot, "    \"{}\" -> \"{}\"", node, next).unwrap();
+                if *weight != 1 {
+                    write!(dot, " [label=\"{}\"]", weight).unwrap();
+                }
+                dot.push_str(";\n");
+            }
+        }
+        dot.push('}');
+        dot
+    }
+}
+
+impl<N: Clone + Ord + Hash + Display> fmt::Display for DiGraph<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_dot())
+    }
+}
+
+// Every node a search reached, with the index of its parent and the distance or cost of the
+// path to it, and the index of the goal if one was found.
+type Search<N, T> = (Vec<(N, Option<usize>, T)>, Option<usize>);
+
+// Follow the parent links from `idx` back to the start of the search.
+fn reconstruct_path<N: Clone, T>(seen: &[(N, Option<usize>, T)], mut idx: usize) -> Vec<N> {
+    let mut path = vec![seen[idx].0.clone()];
+    while let Some(parent) = seen[idx].1 {
+        path.push(seen[parent].0.clone());
+        idx = parent;
+    }
+    path.reverse();
+    path
+}
+
+// Breadth-first search from `start`, stopping early at the first node that `is_goal`.
+fn breadth_first<N, S, I>(
+    start: N,
+    mut successors: S,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Search<N, usize>
+where
+    N: Clone + Eq + Hash,
+    S: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    let mut seen = vec![(start.clone(), None, 0)];
+    let mut visited = HashSet::from([start]);
+    let mut queue = VecDeque::from([0]);
+    while let Some(idx) = queue.pop_front() {
+        let (node, _, distance) = seen[idx].clone();
+        if is_goal(&node) {
+            return (seen, Some(idx));
+        }
+        for next in successors(&node) {
+            if visited.insert(next.clone()) {
+                queue.push_back(seen.len());
+                seen.push((next, Some(idx), distance + 1));
+            }
+        }
+    }
+    (seen, None)
+}
+
+// The shortest path, by number of edges, from `start` to a node that `is_goal`, including both
+// ends.
+pub fn bfs<N, S, I>(start: N, successors: S, is_goal: impl FnMut(&N) -> bool) -> Option<Vec<N>>
+where
+    N: Clone + Eq + Hash,
+    S: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    let (seen, goal) = breadth_first(start, successors, is_goal);
+    goal.map(|idx| reconstruct_path(&seen, idx))
+}
+
+// The number of edges on the shortest path from `start` to every node it can reach.
+pub fn bfs_distances<N, S, I>(start: N, successors: S) -> HashMap<N, usize>
+where
+    N: Clone + Eq + Hash,
+    S: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    let (seen, _) = breadth_first(start, successors, |_| false);
+    seen.into_iter()
+        .map(|(node, _, distance)| (node, distance))
+        .collect()
+}
+
+// Every node reachable from `start`, in the order a recursive depth-first search would first
+// visit them.
+pub fn dfs<N, S, I>(start: N, mut successors: S) -> Vec<N>
+where
+    N: Clone + Eq + Hash,
+    S: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    let mut order = Vec::new();
+    let mut visited = HashSet::new();
+    let mut stack = vec![start];
+    while let Some(node) = stack.pop() {
+        if !visited.insert(node.clone()) {
+            continue;
+        }
+        let next: Vec<N> = successors(&node).into_iter().collect();
+        stack.extend(
+            next.into_iter()
+                .rev()
+                .filter(|next| !visited.contains(next)),
+        );
+        order.push(node);
+    }
+    order
+}
+
+// A* search from `start`, stopping at the first node that `is_goal`. With a `heuristic` that
+// never overestimates the remaining cost this finds the cheapest path; a heuristic of zero makes
+// it Dijkstra's algorithm.
+fn best_first<N, C, S, I>(
+    start: N,
+    mut successors: S,
+    mut heuristic: impl FnMut(&N) -> C,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Search<N, C>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Add<Output = C> + Default,
+    S: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let zero = C::default();
+    let mut heap = BinaryHeap::from([Reverse((heuristic(&start), zero, 0))]);
+    let mut index = HashMap::from([(start.clone(), 0)]);
+    let mut seen = vec![(start, None, zero)];
+    while let Some(Reverse((_, cost, idx))) = heap.pop() {
+        // A cheaper path to this node has been found since this entry was pushed.
+        if cost > seen[idx].2 {
+            continue;
+        }
+        let node = seen[idx].0.clone();
+        if is_goal(&node) {
+            return (seen, Some(idx));
+        }
+        for (next, step) in successors(&node) {
+            let next_cost = cost + step;
+            let next_idx = match index.entry(next) {
+                Entry::Vacant(entry) => {
+                    seen.push((entry.key().clone(), Some(idx), next_cost));
+                    *entry.insert(seen.len() - 1)
+                }
+                Entry::Occupied(entry) => {
+                    let next_idx = *entry.get();
+                    if next_cost >= seen[next_idx].2 {
+                        continue;
+                    }
+                    seen[next_idx].1 = Some(idx);
+                    seen[next_idx].2 = next_cost;
+                    next_idx
+                }
+            };
+            let estimate = next_cost + heuristic(&seen[next_idx].0);
+            heap.push(Reverse((estimate, next_cost, next_idx)));
+        }
+    }
+    (seen, None)
+}
+
+// The cheapest path from `start` to a node that `is_goal`, including both ends, and its cost.
+pub fn dijkstra<N, C, S, I>(
+    start: N,
+    successors: S,
+    is_goal: impl FnMut(&N) -> bool,
+) -> Option<(Vec<N>, C)>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Add<Output = C> + Default,
+    S: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, C)>,
+{
+    astar(start, successors, |_| C::default(), is_goal)
+}
+
+// The cost of the cheapest path from `start` to every node it can reach.
+pub fn dijkstra_all<N, C, S, I>(start: N, successors: S) -> HashMap<N, C>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Add<Output = C> + Default,
+    S: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let (seen, _) = best_first(start, successors, |_| C::default(), |_| false);
+    seen.into_iter()
+        .map(|(node, _, cost)| (node, cost))
+        .collect()
+}
+
+// As for `dijkstra`, but guided towards the goal by `heuristic`, which must never overestimate
+// the cost of reaching a goal from a node.
+pub fn astar<N, C, S, I>(
+    start: N,
+    successors: S,
+    heuristic: impl FnMut(&N) -> C,
+    is_goal: impl FnMut(&N) -> bool,
+) -> Option<(Vec<N>, C)>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Add<Output = C> + Default,
+    S: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let (seen, goal) = best_first(start, successors, heuristic, is_goal);
+    goal.map(|idx| (reconstruct_path(&seen, idx), seen[idx].2))
+}
+
+// The successors of `nodes` and of every node reachable from them, along with the number of
+// edges into each node, for working through a graph in dependency order.
+pub(crate) fn explore<N, S, I>(
+    nodes: impl IntoIterator<Item = N>,
+    mut successors: S,
+) -> (BTreeMap<N, Vec<N>>, BTreeMap<N, usize>)
+where
+    N: Clone + Ord,
+    S: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    let mut edges: BTreeMap<N, Vec<N>> = BTreeMap::new();
+    let mut in_degrees: BTreeMap<N, usize> = BTreeMap::new();
+    let mut unexplored: Vec<N> = nodes.into_iter().collect();
+    while let Some(node) = unexplored.pop() {
+        if edges.contains_key(&node) {
+            continue;
+        }
+        in_degrees.entry(node.clone()).or_insert(0);
+        let next: Vec<N> = successors(&node).into_iter().collect();
+        for next in &next {
+            *in_degrees.entry(next.clone()).or_insert(0) += 1;
+            unexplored.push(next.clone());
+        }
+        edges.insert(node, next);
+    }
+    (edges, in_degrees)
+}
+
+// Order `nodes`, and every node reachable from them, so that each node comes before its
+// successors. When more than one node is ready the smallest goes first, so the order is unique.
+// Returns `None` if there's a cycle.
+pub fn topological_sort<N, S, I>(
+    nodes: impl IntoIterator<Item = N>,
+    successors: S,
+) -> Option<Vec<N>>
+where
+    N: Clone + Ord,
+    S: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    let (edges, mut in_degrees) = explore(nodes, successors);
+
+    let mut ready: BinaryHeap<Reverse<N>> = in_degrees
+        .iter()
+        .filter(|&(_, &in_degree)| in_degree == 0)
+        .map(|(node, _)| Reverse(node.clone()))
+        .collect();
+    let mut order = Vec::with_capacity(edges.len());
+    while let Some(Reverse(node)) = ready.pop() {
+        for next in &edges[&node] {
+            let in_degree = in_degrees.get_mut(next).unwrap();
+            *in_degree -= 1;
+            if *in_degree == 0 {
+                ready.push(Reverse(next.clone()));
+            }
+        }
+        order.push(node);
+    }
+    (order.len() == edges.len()).then_some(order)
+}
+
+// Tarjan's algorithm, with an explicit stack so that deep graphs don't overflow the call stack.
+struct Tarjan<N, S> {
+    successors: S,
+    // Nodes in the order they were discovered, with their lowlinks and whether they're on `stack`.
+    nodes: Vec<(N, usize, bool)>,
+    index: HashMap<N, usize>,
+    stack: Vec<usize>,
+    components: Vec<Vec<N>>,
+}
+
+impl<N, S, I> Tarjan<N, S>
+where
+    N: Clone + Eq + Hash,
+    S: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    fn discover(&mut self, node: N) -> (usize, std::vec::IntoIter<N>) {
+        let idx = self.nodes.len();
+        let next: Vec<N> = (self.successors)(&node).into_iter().collect();
+        self.index.insert(node.clone(), idx);
+        self.nodes.push((node, idx, true));
+        self.stack.push(idx);
+        (idx, next.into_iter())
+    }
+
+    fn visit(&mut self, root: N) {
+        let mut calls = vec![self.discover(root)];
+        while let Some((idx, next)) = calls.last_mut() {
+            let idx = *idx;
+            if let Some(next) = next.next() {
+                match self.index.get(&next) {
+                    None => calls.push(self.discover(next)),
+                    Some(&next_idx) if self.nodes[next_idx].2 => {
+                        self.nodes[idx].1 = self.nodes[idx].1.min(next_idx);
+                    }
+                    Some(_) => {}
+                }
+                continue;
+            }
+            calls.pop();
+            let lowlink = self.nodes[idx].1;
+            if let Some(&(parent, _)) = calls.last() {
+                self.nodes[parent].1 = self.nodes[parent].1.min(lowlink);
+            }
+            if lowlink == idx {
+                let members = self
+                    .stack
+                    .split_off(self.stack.iter().rposition(|&i| i == idx).unwrap());
+                let component = members
+                    .into_iter()
+                    .map(|member| {
+                        self.nodes[member].2 = false;
+                        self.nodes[member].0.clone()
+                    })
+                    .collect();
+                self.components.push(component);
+            }
+        }
+    }
+}
+
+// Group `nodes`, and every node reachable from them, into strongly connected components: sets of
+// nodes that can all reach each other. Components come out in reverse topological order, so no
+// component has an edge into a later one. Within a component, nodes are in discovery order.
+pub fn strongly_connected_components<N, S, I>(
+    nodes: impl IntoIterator<Item = N>,
+    successors: S,
+) -> Vec<Vec<N>>
+where
+    N: Clone + Eq + Hash,
+    S: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    let mut tarjan = Tarjan {
+        successors,
+        nodes: Vec::new(),
+        index: HashMap::new(),
+        stack: Vec::new(),
+        components: Vec::new(),
+    };
+    for node in nodes {
+        if !tarjan.index.contains_key(&node) {
+            tarjan.visit(node);
+        }
+    }
+    tarjan.components
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        astar, bfs, bfs_distances, dfs, dijkstra, dijkstra_all, strongly_connected_components,
+        topological_sort, DiGraph,
+    };
+    use crate::grid::Grid;
+
+    // The example from 2018 day 7: C must come before A and F, and so on.
+    fn steps() -> DiGraph<char> {
+        DiGraph::from_edges([
+            ('C', 'A'),
+            ('C', 'F'),
+            ('A', 'B'),
+            ('A', 'D'),
+            ('B', 'E'),
+            ('D', 'E'),
+            ('F', 'E'),
+        ])
+    }
+
+    #[test]
+    fn check_topological_sort() {
+        let graph = steps();
+        assert_eq!(graph.node_count(), 6);
+        assert_eq!(graph.edge_count(), 7);
+        let order: String = graph.topological_sort().unwrap().into_iter().collect();
+        assert_eq!(order, "CABDFE");
+
+        // Nodes that are only reachable through `successors` are included too.
+        let order = topological_sort([3], |&n: &u32| (n > 0).then(|| n - 1));
+        assert_eq!(order, Some(vec![3, 2, 1, 0]));
+
+        let mut cyclic = steps();
+        cyclic.add_edge('E', 'C');
+        assert_eq!(cyclic.topological_sort(), None);
+    }
+
+    #[test]
+    fn check_bfs_and_dfs() {
+        let graph = steps();
+        assert_eq!(
+            bfs('C', |node| graph.successors(node), |&node| node == 'E'),
+            Some(vec!['C', 'F', 'E'])
+        );
+        assert_eq!(
+            bfs('E', |node| graph.successors(node), |&node| node == 'C'),
+            None
+        );
+        let distances = bfs_distances('C', |node| graph.successors(node));
+        assert_eq!((distances.len(), distances[&'E']), (6, 2));
+        assert_eq!(
+            dfs('C', |node| graph.successors(node)),
+            vec!['C', 'A', 'B', 'E', 'D', 'F']
+        );
+    }
+
+    #[test]
+    fn check_weighted_searches() {
+        // The cheapest route across the grid, entering each cell costs its digit.
+        let grid = Grid::from_rows(vec![vec![1, 1, 6], vec![1, 3, 8], vec![2, 1, 3]]);
+        let successors = |&(x, y): &(usize, usize)| {
+            grid.neighbours4(x, y)
+                .map(|pos| (pos, grid[pos] as u64))
+                .collect::<Vec<_>>()
+        };
+        let goal = (2, 2);
+        let (path, cost) = dijkstra((0, 0), successors, |&pos| pos == goal).unwrap();
+        assert_eq!(cost, 7);
+        assert_eq!(path, vec![(0, 0), (0, 1), (0, 2), (1, 2), (2, 2)]);
+        let manhattan = |&(x, y): &(usize, usize)| (goal.0 - x + goal.1 - y) as u64;
+        assert_eq!(
+            astar((0, 0), successors, manhattan, |&pos| pos == goal),
+            Some((path, cost))
+        );
+        assert_eq!(dijkstra_all((0, 0), successors)[&(2, 0)], 7);
+
+        let mut graph = DiGraph::new();
+        graph.add_weighted_edge("a", "b", 5);
+        graph.add_weighted_edge("a", "c", 1);
+        graph.add_weighted_edge("c", "b", 1);
+        let (path, cost) =
+            dijkstra("a", |node| graph.weighted_successors(node), |&n| n == "b").unwrap();
+        assert_eq!((path, cost), (vec!["a", "c", "b"], 2));
+    }
+
+    #[test]
+    fn check_strongly_connected_components() {
+        let graph = DiGraph::from_edges([(1, 2), (2, 3), (3, 1), (3, 4), (4, 5), (5, 4), (6, 5)]);
+        assert_eq!(
+            graph.strongly_connected_components(),
+            vec![vec![4, 5], vec![1, 2, 3], vec![6]]
+        );
+        // A long chain is fine without recursion.
+        let components =
+            strongly_connected_components([0], |&n: &u32| (n < 100_000).then(|| n + 1));
+        assert_eq!(components.len(), 100_001);
+    }
+
+    #[test]
+    fn check_dot_export() {
+        let mut graph = DiGraph::from_edges([("a", "b")]);
+        graph.add_weighted_edge("b", "a", 3);
+        assert_eq!(
+            graph.to_dot(),
+            "digraph {\n    \"a\";\n    \"a\" -> \"b\";\n    \"b\";\n    \"b\" -> \"a\" [label=\"3\"];\n}"
+        );
+    }
+}
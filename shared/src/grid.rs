@@ -0,0 +1,305 @@
+// A rectangular grid of cells, indexed by `(x, y)` from the top left corner.
+//
+// The cells are stored row by row, so `grid[(x, y)]` is `cells[y * width + x]`.
+use crate::parse_error::ParseError;
+use crate::parser::fixed_width_grid;
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+const OFFSETS_4: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+const OFFSETS_8: [(isize, isize); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+];
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn from_fn(width: usize, height: usize, mut f: impl FnMut(usize, usize) -> T) -> Self {
+        let cells = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| f(x, y))
+            .collect();
+        Grid {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    // Every row must have the same length.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Self {
+        let width = rows.first().map_or(0, Vec::len);
+        assert!(
+            rows.iter().all(|row| row.len() == width),
+            "Every row of a grid must have the same length."
+        );
+        Grid {
+            width,
+            height: rows.len(),
+            cells: rows.into_iter().flatten().collect(),
+        }
+    }
+
+    // Parse `input_lines[block]`, converting each character with `cell`.
+    pub fn parse(
+        input_lines: &[Vec<String>],
+        block: usize,
+        cell: impl Fn(char) -> Option<T>,
+        expected: &str,
+    ) -> Result<Self, ParseError> {
+        fixed_width_grid(input_lines, block, cell, expected).map(Grid::from_rows)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, x: isize, y: isize) -> bool {
+        (0..self.width as isize).contains(&x) && (0..self.height as isize).contains(&y)
+    }
+
+    pub fn get(&self, x: isize, y: isize) -> Option<&T> {
+        self.contains(x, y)
+            .then(|| &self.cells[y as usize * self.width + x as usize])
+    }
+
+    pub fn get_mut(&mut self, x: isize, y: isize) -> Option<&mut T> {
+        if self.contains(x, y) {
+            Some(&mut self.cells[y as usize * self.width + x as usize])
+        } else {
+            None
+        }
+    }
+
+    // Treat the grid as repeating forever in every direction. The grid must not be empty.
+    pub fn get_wrapping(&self, x: isize, y: isize) -> &T {
+        assert!(!self.cells.is_empty(), "An empty grid can't be wrapped.");
+        let x = x.rem_euclid(self.width as isize) as usize;
+        let y = y.rem_euclid(self.height as isize) as usize;
+        &self[(x, y)]
+    }
+
+    // Every position, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    // Every position on the outside edge of the grid, each one once.
+    pub fn edge_positions(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.positions()
+            .filter(|&(x, y)| x == 0 || y == 0 || x == self.width - 1 || y == self.height - 1)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        self.positions().zip(&self.cells)
+    }
+
+    // The positions above, right, below and left of `(x, y)` that are within the grid.
+    pub fn neighbours4(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.neighbours(x, y, &OFFSETS_4)
+    }
+
+    // The positions around `(x, y)`, including diagonals, that are within the grid.
+    pub fn neighbours8(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.neighbours(x, y, &OFFSETS_8)
+    }
+
+    fn neighbours<'a>(
+        &'a self,
+        x: usize,
+        y: usize,
+        offsets: &'a [(isize, isize)],
+    ) -> impl Iterator<Item = (usize, usize)> + 'a {
+        offsets
+            .iter()
+            .map(move |&(dx, dy)| (x as isize + dx, y as isize + dy))
+            .filter(|&(x, y)| self.contains(x, y))
+            .map(|(x, y)| (x as usize, y as usize))
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        assert!(y < self.height, "y = {} is outside the grid.", y);
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // `chunks` panics on a chunk size of zero.
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        assert!(x < self.width, "x = {} is outside the grid.", x);
+        self.cells[x..].iter().step_by(self.width)
+    }
+
+    // The cells of the `width` x `height` region with its top left corner at `(x, y)`, row by
+    // row. The region must fit within the grid.
+    pub fn region(
+        &self,
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+    ) -> impl Iterator<Item = &T> {
+        assert!(x + width <= self.width && y + height <= self.height);
+        (y..y + height).flat_map(move |row| &self.row(row)[x..x + width])
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self {
+        Grid {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    // A copy of a region of the grid, as for `region`.
+    pub fn subgrid(&self, x: usize, y: usize, width: usize, height: usize) -> Grid<T> {
+        Grid {
+            width,
+            height,
+            cells: self.region(x, y, width, height).cloned().collect(),
+        }
+    }
+
+    pub fn transposed(&self) -> Grid<T> {
+        Grid::from_fn(self.height, self.width, |x, y| self[(y, x)].clone())
+    }
+}
+
+impl Grid<char> {
+    // Parse `input_lines[block]` as a grid of characters.
+    pub fn parse_chars(input_lines: &[Vec<String>], block: usize) -> Result<Self, ParseError> {
+        Grid::parse(input_lines, block, Some, "a character")
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (x, y): (usize, usize)) -> &T {
+        assert!(x < self.width, "x = {} is outside the grid.", x);
+        &self.cells[y * self.width + x]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut T {
+        assert!(x < self.width, "x = {} is outside the grid.", x);
+        &mut self.cells[y * self.width + x]
+    }
+}
+
+// One line per row, with the cells' renderings run together.
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Grid;
+
+    fn example() -> Grid<char> {
+        let input_lines = vec![vec!["#..".to_string(), ".#.".to_string()]];
+        Grid::parse_chars(&input_lines, 0).unwrap()
+    }
+
+    #[test]
+    fn check_parse_and_display() {
+        let grid = example();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(1, 1)], '#');
+        assert_eq!(grid.to_string(), "#..\n.#.");
+        let input_lines = vec![vec!["#..".to_string(), ".#".to_string()]];
+        assert!(Grid::parse_chars(&input_lines, 0).is_err());
+    }
+
+    #[test]
+    fn check_access() {
+        let grid = example();
+        assert_eq!(grid.get(0, 0), Some(&'#'));
+        assert_eq!(grid.get(3, 0), None);
+        assert_eq!(grid.get(0, -1), None);
+        assert_eq!(grid.get_wrapping(4, 3), &'#');
+        assert_eq!(grid.get_wrapping(-3, -2), &'#');
+    }
+
+    #[test]
+    fn check_neighbours() {
+        let grid = Grid::new(3, 3, 0);
+        assert_eq!(
+            grid.neighbours4(0, 0).collect::<Vec<_>>(),
+            vec![(1, 0), (0, 1)]
+        );
+        assert_eq!(grid.neighbours4(1, 1).count(), 4);
+        assert_eq!(grid.neighbours8(1, 1).count(), 8);
+        assert_eq!(grid.neighbours8(2, 2).count(), 3);
+        assert_eq!(grid.edge_positions().count(), 8);
+    }
+
+    #[test]
+    fn check_views() {
+        let grid = Grid::from_fn(3, 2, |x, y| 10 * y + x);
+        assert_eq!(grid.row(1), &[10, 11, 12]);
+        assert_eq!(grid.column(2).copied().collect::<Vec<_>>(), vec![2, 12]);
+        assert_eq!(
+            grid.transposed(),
+            Grid::from_rows(vec![vec![0, 10], vec![1, 11], vec![2, 12]])
+        );
+        assert_eq!(grid.region(1, 0, 2, 2).sum::<usize>(), 1 + 2 + 11 + 12);
+        assert_eq!(
+            grid.subgrid(1, 1, 2, 1),
+            Grid::from_rows(vec![vec![11, 12]])
+        );
+        assert_eq!(grid.map(|cell| cell % 10).to_string(), "012\n012");
+    }
+
+    #[test]
+    #[should_panic(expected = "x = 4 is outside the grid.")]
+    fn check_column_outside_grid() {
+        Grid::new(3, 2, 0).column(4).count();
+    }
+
+    #[test]
+    #[should_panic(expected = "An empty grid can't be wrapped.")]
+    fn check_wrapping_empty_grid() {
+        Grid::new(0, 0, 0).get_wrapping(1, 1);
+    }
+}
@@ -0,0 +1,481 @@
+// Half-open intervals, sets of them and boxes made of them.
+//
+// An `Interval` covers `start..end`, so it's empty when `start >= end`, and two intervals that
+// share an endpoint touch without overlapping. This makes splitting an interval at a point exact.
+use crate::point::{BoundingBox, Point};
+use std::fmt;
+use std::iter::FromIterator;
+use std::ops::{Add, Range, Sub};
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Interval<T> {
+    pub start: T,
+    pub end: T,
+}
+
+impl<T: Copy + Ord> Interval<T> {
+    pub fn new(start: T, end: T) -> Self {
+        Interval { start, end }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start >= self.end
+    }
+
+    pub fn contains(&self, value: &T) -> bool {
+        self.start <= *value && *value < self.end
+    }
+
+    pub fn overlaps(&self, other: &Self) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    // The values in both intervals, or `None` if there aren't any.
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let common = Interval::new(self.start.max(other.start), self.end.min(other.end));
+        (!common.is_empty()).then_some(common)
+    }
+
+    // The smallest interval covering both, if they overlap or touch.
+    pub fn union(&self, other: &Self) -> Option<Self> {
+        (self.start <= other.end && other.start <= self.end)
+            .then(|| Interval::new(self.start.min(other.start), self.end.max(other.end)))
+    }
+
+    // The values in `self` but not `other`: up to one piece either side of `other`.
+    pub fn difference(&self, other: &Self) -> Vec<Self> {
+        if !self.overlaps(other) {
+            return if self.is_empty() { vec![] } else { vec![*self] };
+        }
+        [
+            Interval::new(self.start, other.start),
+            Interval::new(other.end, self.end),
+        ]
+        .into_iter()
+        .filter(|piece| !piece.is_empty())
+        .collect()
+    }
+
+    // The parts of the interval below `value` and from `value` onwards.
+    pub fn split_at(&self, value: T) -> (Option<Self>, Option<Self>) {
+        let below = Interval::new(self.start, value.min(self.end));
+        let above = Interval::new(value.max(self.start), self.end);
+        (
+            (!below.is_empty()).then_some(below),
+            (!above.is_empty()).then_some(above),
+        )
+    }
+}
+
+impl<T: Copy + Ord + Add<Output = T>> Interval<T> {
+    pub fn from_len(start: T, len: T) -> Self {
+        Interval::new(start, start + len)
+    }
+}
+
+impl<T: Copy + Ord + Sub<Output = T> + Default> Interval<T> {
+    // The number of values in the interval; zero if it's empty.
+    pub fn len(&self) -> T {
+        if self.is_empty() {
+            T::default()
+        } else {
+            self.end - self.start
+        }
+    }
+}
+
+impl<T> From<Range<T>> for Interval<T> {
+    fn from(range: Range<T>) -> Self {
+        Interval {
+            start: range.start,
+            end: range.end,
+        }
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Interval<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}, {})", self.start, self.end)
+    }
+}
+
+// A set of values stored as sorted intervals that don't overlap or touch.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct IntervalSet<T> {
+    intervals: Vec<Interval<T>>,
+}
+
+impl<T: Copy + Ord> IntervalSet<T> {
+    pub fn new() -> Self {
+        IntervalSet {
+            intervals: Vec::new(),
+        }
+    }
+
+    // Sort the intervals, drop the empty ones and merge any that overlap or touch.
+    fn normalise(mut intervals: Vec<Interval<T>>) -> Self {
+        intervals.retain(|interval| !interval.is_empty());
+        intervals.sort_unstable();
+        let mut merged: Vec<Interval<T>> = Vec::with_capacity(intervals.len());
+        for interval in intervals {
+            match merged.last_mut().and_then(|last| last.union(&interval)) {
+                Some(union) => *merged.last_mut().unwrap() = union,
+                None => merged.push(interval),
+            }
+        }
+        IntervalSet { intervals: merged }
+    }
+
+    pub fn intervals(&self) -> &[Interval<T>] {
+        &self.intervals
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Interval<T>> {
+        self.intervals.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    pub fn min(&self) -> Option<T> {
+        self.intervals.first().map(|interval| interval.start)
+    }
+
+    pub fn contains(&self, value: &T) -> bool {
+        // The last interval starting at or before `value` is the only one that can contain it.
+        let idx = self
+            .intervals
+            .partition_point(|interval| interval.start <= *value);
+        idx > 0 && self.intervals[idx - 1].contains(value)
+    }
+
+    pub fn insert(&mut self, interval: Interval<T>) {
+        let mut intervals = std::mem::take(&mut self.intervals);
+        intervals.push(interval);
+        *self = Self::normalise(intervals);
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        Self::normalise(self.iter().chain(other.iter()).copied().collect())
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut intervals = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while i < self.intervals.len() && j < other.intervals.len() {
+            let (a, b) = (self.intervals[i], other.intervals[j]);
+            intervals.extend(a.intersection(&b));
+            // Move past whichever interval finishes first; the other may overlap the next one.
+            if a.end < b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        IntervalSet { intervals }
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut intervals = Vec::new();
+        let mut first = 0;
+        for interval in &self.intervals {
+            // Skip the intervals of `other` that finish before this one starts. The rest may
+            // reach into the following intervals too, so `first` only moves past these.
+            while first < other.intervals.len() && other.intervals[first].end <= interval.start {
+                first += 1;
+            }
+            let mut start = interval.start;
+            for removed in other.intervals[first..]
+                .iter()
+                .take_while(|removed| removed.start < interval.end)
+            {
+                if removed.start > start {
+                    intervals.push(Interval::new(start, removed.start));
+                }
+                start = start.max(removed.end);
+            }
+            if start < interval.end {
+                intervals.push(Interval::new(start, interval.end));
+            }
+        }
+        IntervalSet { intervals }
+    }
+}
+
+impl<T: Copy + Ord + Sub<Output = T> + Add<Output = T> + Default> IntervalSet<T> {
+    // The number of values in the set.
+    pub fn size(&self) -> T {
+        self.iter()
+            .fold(T::default(), |size, interval| size + interval.len())
+    }
+}
+
+impl<T: Copy + Ord> From<Interval<T>> for IntervalSet<T> {
+    fn from(interval: Interval<T>) -> Self {
+        Self::normalise(vec![interval])
+    }
+}
+
+impl<T: Copy + Ord> FromIterator<Interval<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Interval<T>>>(iter: I) -> Self {
+        Self::normalise(iter.into_iter().collect())
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for IntervalSet<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{{")?;
+        for (idx, interval) in self.intervals.iter().enumerate() {
+            if idx > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", interval)?;
+        }
+        write!(f, "}}")
+    }
+}
+
+// A map that moves each source interval so that it starts at a destination value, and leaves
+// every other value where it is. Where source intervals overlap, the first one added wins.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct PiecewiseMap<T> {
+    pieces: Vec<(Interval<T>, T)>,
+}
+
+impl<T: Copy + Ord + Add<Output = T> + Sub<Output = T>> PiecewiseMap<T> {
+    pub fn new() -> Self {
+        PiecewiseMap { pieces: Vec::new() }
+    }
+
+    pub fn add(&mut self, source: Interval<T>, destination: T) {
+        self.pieces.push((source, destination));
+    }
+
+    pub fn map(&self, value: T) -> T {
+        self.pieces
+            .iter()
+            .find(|(source, _)| source.contains(&value))
+            .map_or(value, |&(source, destination)| {
+                destination + (value - source.start)
+            })
+    }
+
+    // Map every value in `set`, splitting its intervals wherever a source interval starts or ends.
+    pub fn map_set(&self, set: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut unmapped = set.clone();
+        let mut mapped = Vec::new();
+        for &(source, destination) in &self.pieces {
+            let source = IntervalSet::from(source);
+            mapped.extend(unmapped.intersection(&source).iter().map(|interval| {
+                Interval::new(
+                    destination + (interval.start - source.intervals[0].start),
+                    destination + (interval.end - source.intervals[0].start),
+                )
+            }));
+            unmapped = unmapped.difference(&source);
+        }
+        mapped.extend(unmapped.intervals);
+        mapped.into_iter().collect()
+    }
+}
+
+impl<T: Copy + Ord + Add<Output = T> + Sub<Output = T>> FromIterator<(Interval<T>, T)>
+    for PiecewiseMap<T>
+{
+    fn from_iter<I: IntoIterator<Item = (Interval<T>, T)>>(iter: I) -> Self {
+        PiecewiseMap {
+            pieces: iter.into_iter().collect(),
+        }
+    }
+}
+
+// An N-dimensional box made of one interval along each axis: a rectangle when N is 2 and a
+// cuboid when N is 3. Like its intervals, it doesn't include its far faces.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Cuboid<const N: usize>(pub [Interval<i64>; N]);
+
+impl<const N: usize> Cuboid<N> {
+    pub fn from_corner_and_size(corner: Point<N>, size: Point<N>) -> Self {
+        Cuboid(std::array::from_fn(|axis| {
+            Interval::from_len(corner[axis], size[axis])
+        }))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.iter().any(Interval::is_empty)
+    }
+
+    // The same lattice points as a `BoundingBox`, which includes its far faces, or `None` if the
+    // box is empty.
+    pub fn bounding_box(&self) -> Option<BoundingBox<N>> {
+        (!self.is_empty()).then(|| BoundingBox {
+            min: Point(self.0.map(|interval| interval.start)),
+            max: Point(self.0.map(|interval| interval.end - 1)),
+        })
+    }
+
+    pub fn contains(&self, point: &Point<N>) -> bool {
+        self.bounding_box()
+            .is_some_and(|bounds| bounds.contains(point))
+    }
+
+    // The number of lattice points in the box.
+    pub fn volume(&self) -> i64 {
+        self.bounding_box().map_or(0, |bounds| bounds.volume())
+    }
+
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let common = Cuboid(std::array::from_fn(|axis| {
+            Interval::new(
+                self.0[axis].start.max(other.0[axis].start),
+                self.0[axis].end.min(other.0[axis].end),
+            )
+        }));
+        (!common.is_empty()).then_some(common)
+    }
+
+    pub fn overlaps(&self, other: &Self) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    // The parts of `self` outside `other`, as up to 2N boxes that don't overlap.
+    pub fn difference(&self, other: &Self) -> Vec<Self> {
+        let Some(common) = self.intersection(other) else {
+            return if self.is_empty() { vec![] } else { vec![*self] };
+        };
+        // Slice off the parts below and above `common` one axis at a time, narrowing what's left.
+        let mut pieces = Vec::new();
+        let mut rest = *self;
+        for axis in 0..N {
+            let (below, _) = rest.0[axis].split_at(common.0[axis].start);
+            let (_, above) = rest.0[axis].split_at(common.0[axis].end);
+            for slice in below.into_iter().chain(above) {
+                let mut piece = rest;
+                piece.0[axis] = slice;
+                pieces.push(piece);
+            }
+            rest.0[axis] = common.0[axis];
+        }
+        pieces
+    }
+
+    // Every lattice point in the box, in the order of `BoundingBox::points`.
+    pub fn points(&self) -> impl Iterator<Item = Point<N>> {
+        self.bounding_box()
+            .into_iter()
+            .flat_map(|bounds| bounds.points())
+    }
+}
+
+impl<const N: usize> From<BoundingBox<N>> for Cuboid<N> {
+    fn from(bounds: BoundingBox<N>) -> Self {
+        Cuboid(std::array::from_fn(|axis| {
+            Interval::new(bounds.min[axis], bounds.max[axis] + 1)
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Cuboid, Interval, IntervalSet, PiecewiseMap};
+    use crate::point::Point;
+
+    #[test]
+    fn check_interval() {
+        let a = Interval::new(2, 8);
+        let b = Interval::from(5..12);
+        assert_eq!((a.len(), Interval::new(3, 1).len()), (6, 0));
+        assert!(a.contains(&2) && !a.contains(&8));
+        assert_eq!(a.intersection(&b), Some(Interval::new(5, 8)));
+        assert_eq!(a.intersection(&Interval::new(8, 9)), None);
+        assert_eq!(a.union(&Interval::new(8, 9)), Some(Interval::new(2, 9)));
+        assert_eq!(a.union(&Interval::new(9, 10)), None);
+        assert_eq!(
+            a.difference(&Interval::new(4, 5)),
+            vec![(2..4).into(), (5..8).into()]
+        );
+        assert_eq!(a.difference(&b), vec![Interval::new(2, 5)]);
+        assert_eq!(a.split_at(4), (Some((2..4).into()), Some((4..8).into())));
+        assert_eq!(a.split_at(1), (None, Some(a)));
+        assert_eq!(a.to_string(), "[2, 8)");
+    }
+
+    #[test]
+    fn check_interval_set() {
+        let set: IntervalSet<i32> = [5..7, 0..2, 1..3, 7..8, 4..4]
+            .into_iter()
+            .map(Interval::from)
+            .collect();
+        assert_eq!(set.to_string(), "{[0, 3), [5, 8)}");
+        assert_eq!((set.size(), set.min()), (6, Some(0)));
+        assert!(set.contains(&2) && !set.contains(&3) && set.contains(&7));
+
+        let other: IntervalSet<i32> = [Interval::new(2, 6), Interval::new(7, 10)]
+            .into_iter()
+            .collect();
+        assert_eq!(set.union(&other).to_string(), "{[0, 10)}");
+        assert_eq!(
+            set.intersection(&other).to_string(),
+            "{[2, 3), [5, 6), [7, 8)}"
+        );
+        assert_eq!(set.difference(&other).to_string(), "{[0, 2), [6, 7)}");
+        assert_eq!(other.difference(&set).to_string(), "{[3, 5), [8, 10)}");
+
+        let mut inserted = set.clone();
+        inserted.insert(Interval::new(3, 5));
+        assert_eq!(inserted.to_string(), "{[0, 8)}");
+    }
+
+    #[test]
+    fn check_piecewise_map() {
+        // The seed-to-soil map from 2023 day 5.
+        let map: PiecewiseMap<u64> = [
+            (Interval::from_len(98, 2), 50),
+            (Interval::from_len(50, 48), 52),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!((map.map(79), map.map(98), map.map(10)), (81, 50, 10));
+        let seeds: IntervalSet<u64> = [Interval::from_len(45, 10), Interval::from_len(95, 10)]
+            .into_iter()
+            .collect();
+        // The pieces are 45..50 (unmapped), 50..55 -> 52..57, 95..98 -> 97..100, 98..100 -> 50..52
+        // and 100..105 (unmapped), which merge back together.
+        assert_eq!(map.map_set(&seeds).to_string(), "{[45, 57), [97, 105)}");
+        let split: IntervalSet<u64> = [Interval::from_len(95, 5)].into_iter().collect();
+        assert_eq!(map.map_set(&split).to_string(), "{[50, 52), [97, 100)}");
+    }
+
+    #[test]
+    fn check_cuboid() {
+        let a = Cuboid::from_corner_and_size(Point([1, 3]), Point([4, 4]));
+        let b = Cuboid::from_corner_and_size(Point([3, 1]), Point([4, 4]));
+        let c = Cuboid::from_corner_and_size(Point([5, 4]), Point([2, 2]));
+        assert_eq!(a.volume(), 16);
+        assert_eq!(a.intersection(&b).map(|common| common.volume()), Some(4));
+        assert!(!a.overlaps(&c) && b.overlaps(&c));
+        assert!(a.contains(&Point([4, 6])) && !a.contains(&Point([5, 6])));
+
+        let pieces = a.difference(&b);
+        assert_eq!(pieces.iter().map(Cuboid::volume).sum::<i64>(), 12);
+        assert!(pieces.iter().all(|piece| !piece.overlaps(&b)));
+        assert_eq!(a.difference(&c), vec![a]);
+
+        let unit = Cuboid::from_corner_and_size(Point([0, 0, 0]), Point([3, 3, 3]));
+        let core = Cuboid::from_corner_and_size(Point([1, 1, 1]), Point([1, 1, 1]));
+        assert_eq!(unit.difference(&core).len(), 6);
+        assert_eq!(core.points().collect::<Vec<_>>(), vec![Point([1, 1, 1])]);
+        assert_eq!(a.points().count(), 16);
+        assert_eq!(
+            Cuboid([Interval::new(2, 2), Interval::new(0, 5)])
+                .points()
+                .count(),
+            0
+        );
+
+        let bounds = a.bounding_box().unwrap();
+        assert_eq!((bounds.min, bounds.max), (Point([1, 3]), Point([4, 6])));
+        assert_eq!(Cuboid::from(bounds), a);
+    }
+}
@@ -0,0 +1,20 @@
+// Code shared between the years. Add it to a year with
+//   shared = { path = "../shared" }
+// in the `[dependencies]` section of that year's `Cargo.toml`.
+pub mod circular_list;
+pub mod cycle;
+pub mod diff_impl;
+pub mod fuzz;
+pub mod graph;
+pub mod grid;
+pub mod interval;
+pub mod memo;
+pub mod number_theory;
+pub mod ocr;
+pub mod parse_error;
+pub mod parser;
+pub mod point;
+pub mod scale;
+pub mod schedule;
+pub mod summed_area;
+pub mod union_find;
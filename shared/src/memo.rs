@@ -0,0 +1,205 @@
+// Memoised recursion, for dynamic programming over any hashable key.
+//
+// The function being memoised is given a `recurse` callback to use in place of calling itself,
+// so that the answers to its subproblems are cached too:
+//
+//   let mut fib = Memoised::new(|recurse, &n: &u64| match n {
+//       0 | 1 => n,
+//       _ => recurse(n - 1) + recurse(n - 2),
+//   });
+//   assert_eq!(fib.call(90), 2_880_067_194_370_816_120);
+//
+// The cache can be bounded, in which case the oldest entries are evicted first, and it keeps
+// count of its hits and misses, which show up in its `Debug` output.
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::hash::Hash;
+
+type Function<'a, K, V> = dyn Fn(&mut dyn FnMut(K) -> V, &K) -> V + 'a;
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Stats {
+    pub hits: usize,
+    pub misses: usize,
+    pub evictions: usize,
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let lookups = self.hits + self.misses;
+        let hit_rate = if lookups == 0 {
+            0.0
+        } else {
+            100.0 * self.hits as f64 / lookups as f64
+        };
+        write!(
+            f,
+            "{} hits, {} misses ({:.1}% hit rate), {} evictions",
+            self.hits, self.misses, hit_rate, self.evictions
+        )
+    }
+}
+
+struct Cache<K, V> {
+    values: HashMap<K, V>,
+    // Keys in the order they were added, for evicting the oldest.
+    order: VecDeque<K>,
+    capacity: Option<usize>,
+    stats: Stats,
+}
+
+impl<K: Clone + Eq + Hash, V: Clone> Cache<K, V> {
+    fn get(&mut self, key: K, function: &Function<K, V>) -> V {
+        if let Some(value) = self.values.get(&key) {
+            self.stats.hits += 1;
+            return value.clone();
+        }
+        self.stats.misses += 1;
+        let value = function(&mut |subproblem| self.get(subproblem, function), &key);
+        self.insert(key, value.clone());
+        value
+    }
+
+    fn insert(&mut self, key: K, value: V) {
+        if self.capacity == Some(0) {
+            return;
+        }
+        if self.capacity == Some(self.values.len()) {
+            let oldest = self.order.pop_front().unwrap();
+            self.values.remove(&oldest);
+            self.stats.evictions += 1;
+        }
+        if self.capacity.is_some() {
+            self.order.push_back(key.clone());
+        }
+        self.values.insert(key, value);
+    }
+}
+
+pub struct Memoised<'a, K, V> {
+    function: Box<Function<'a, K, V>>,
+    cache: Cache<K, V>,
+}
+
+impl<'a, K: Clone + Eq + Hash, V: Clone> Memoised<'a, K, V> {
+    // Memoise `function`, with no limit on the size of the cache.
+    pub fn new(function: impl Fn(&mut dyn FnMut(K) -> V, &K) -> V + 'a) -> Self {
+        Memoised {
+            function: Box::new(function),
+            cache: Cache {
+                values: HashMap::new(),
+                order: VecDeque::new(),
+                capacity: None,
+                stats: Stats::default(),
+            },
+        }
+    }
+
+    // Memoise `function`, keeping at most `capacity` answers.
+    pub fn with_capacity(
+        capacity: usize,
+        function: impl Fn(&mut dyn FnMut(K) -> V, &K) -> V + 'a,
+    ) -> Self {
+        let mut memoised = Self::new(function);
+        memoised.cache.capacity = Some(capacity);
+        memoised
+    }
+
+    // The value of the function for `key`, from the cache if it's there.
+    pub fn call(&mut self, key: K) -> V {
+        self.cache.get(key, &*self.function)
+    }
+
+    // The number of answers in the cache.
+    pub fn len(&self) -> usize {
+        self.cache.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.values.is_empty()
+    }
+
+    pub fn stats(&self) -> Stats {
+        self.cache.stats
+    }
+
+    // Forget every answer, e.g. between the two parts of a puzzle when the function's inputs
+    // change. The statistics are kept.
+    pub fn clear(&mut self) {
+        self.cache.values.clear();
+        self.cache.order.clear();
+    }
+}
+
+impl<K, V> fmt::Debug for Memoised<'_, K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Memoised")
+            .field("len", &self.cache.values.len())
+            .field("capacity", &self.cache.capacity)
+            .field("stats", &format_args!("{}", self.cache.stats))
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Memoised, Stats};
+    use std::cell::Cell;
+
+    #[test]
+    fn check_recursion_is_cached() {
+        let mut fib = Memoised::new(|recurse, &n: &u64| {
+            if n < 2 {
+                n
+            } else {
+                recurse(n - 1) + recurse(n - 2)
+            }
+        });
+        assert_eq!(fib.call(90), 2_880_067_194_370_816_120);
+        // Each of 0..=90 is worked out once, and from 3 up n - 2 is cached by the time it's needed.
+        assert_eq!(
+            fib.stats(),
+            Stats {
+                hits: 88,
+                misses: 91,
+                evictions: 0
+            }
+        );
+        assert_eq!(fib.call(50), 12_586_269_025);
+        assert_eq!((fib.len(), fib.stats().hits), (91, 89));
+        assert_eq!(
+            format!("{:?}", fib),
+            "Memoised { len: 91, capacity: None, stats: 89 hits, 91 misses (49.4% hit rate), 0 evictions }"
+        );
+        fib.clear();
+        assert!(fib.is_empty());
+    }
+
+    #[test]
+    fn check_bounded_cache() {
+        // Count the calls, to check which answers were kept.
+        let calls = Cell::new(0);
+        let mut square = Memoised::with_capacity(2, |_, &n: &u32| {
+            calls.set(calls.get() + 1);
+            n * n
+        });
+        for n in [1, 2, 1, 3, 1, 3] {
+            square.call(n);
+        }
+        // 3 pushes 1 out, as the oldest, so 1 has to be worked out again, which pushes out 2.
+        assert_eq!(calls.get(), 4);
+        assert_eq!(
+            square.stats(),
+            Stats {
+                hits: 2,
+                misses: 4,
+                evictions: 2
+            }
+        );
+        assert_eq!(square.len(), 2);
+
+        let mut uncached = Memoised::with_capacity(0, |_, &n: &u32| n + 1);
+        assert_eq!((uncached.call(1), uncached.call(1)), (2, 2));
+        assert_eq!((uncached.len(), uncached.stats().misses), (0, 2));
+    }
+}
@@ -0,0 +1,205 @@
+// Integer and modular arithmetic on `i128`.
+//
+// Moduli are positive and residues are returned in `0..m`. Products are taken with `mul_mod`,
+// which falls back to doubling and adding when they'd overflow, so any modulus that fits in an
+// `i128` is safe.
+use std::collections::HashMap;
+
+// The greatest common divisor, which is never negative. `gcd(0, 0)` is 0.
+pub fn gcd(a: i128, b: i128) -> i128 {
+    extended_gcd(a, b).0
+}
+
+// The greatest common divisor `g` of `a` and `b`, with `x` and `y` such that `a * x + b * y == g`.
+pub fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+    while r != 0 {
+        let quotient = old_r / r;
+        (old_r, r) = (r, old_r - quotient * r);
+        (old_x, x) = (x, old_x - quotient * x);
+        (old_y, y) = (y, old_y - quotient * y);
+    }
+    if old_r < 0 {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+// The least common multiple, which is never negative. Returns `None` if it doesn't fit.
+pub fn lcm(a: i128, b: i128) -> Option<i128> {
+    if a == 0 || b == 0 {
+        return Some(0);
+    }
+    (a / gcd(a, b)).checked_mul(b).map(i128::abs)
+}
+
+// The least common multiple of all of `numbers`, or 1 if there aren't any.
+pub fn lcm_all(numbers: impl IntoIterator<Item = i128>) -> Option<i128> {
+    numbers.into_iter().try_fold(1, lcm)
+}
+
+// `a + b` modulo `m`, for `a` and `b` in `0..m`, without overflowing.
+fn add_mod(a: i128, b: i128, m: i128) -> i128 {
+    if a >= m - b {
+        a - (m - b)
+    } else {
+        a + b
+    }
+}
+
+// `a * b` modulo `m`, without overflowing.
+pub fn mul_mod(a: i128, b: i128, m: i128) -> i128 {
+    let (a, mut b) = (a.rem_euclid(m), b.rem_euclid(m));
+    if let Some(product) = a.checked_mul(b) {
+        return product % m;
+    }
+    // Add up `a * 2^k` modulo `m` for each bit `k` set in `b`.
+    let (mut result, mut power) = (0, a);
+    while b > 0 {
+        if b & 1 == 1 {
+            result = add_mod(result, power, m);
+        }
+        power = add_mod(power, power, m);
+        b >>= 1;
+    }
+    result
+}
+
+// `base` to the power `exp`, modulo `m`.
+pub fn mod_pow(base: i128, mut exp: u128, m: i128) -> i128 {
+    let mut base = base.rem_euclid(m);
+    let mut result = 1 % m;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul_mod(result, base, m);
+        }
+        base = mul_mod(base, base, m);
+        exp >>= 1;
+    }
+    result
+}
+
+// The `x` in `0..m` with `a * x` congruent to 1 modulo `m`, if `a` and `m` are coprime.
+pub fn mod_inverse(a: i128, m: i128) -> Option<i128> {
+    let (g, x, _) = extended_gcd(a.rem_euclid(m), m);
+    (g == 1).then(|| x.rem_euclid(m))
+}
+
+// Solve the simultaneous congruences `x = residue (mod modulus)`, returning the solution as
+// `(residue, modulus)` modulo the lcm of the moduli. The moduli don't need to be coprime, but
+// then the congruences may contradict each other, in which case there's no solution.
+pub fn crt(congruences: impl IntoIterator<Item = (i128, i128)>) -> Option<(i128, i128)> {
+    congruences
+        .into_iter()
+        .try_fold((0, 1), |(r1, m1), (r2, m2)| {
+            let (r2, g) = (r2.rem_euclid(m2), gcd(m1, m2));
+            let difference = r2 - r1;
+            if difference % g != 0 {
+                return None;
+            }
+            // x = r1 + m1 * t, where m1 * t = r2 - r1 (mod m2), so t = (r2 - r1) / g * (m1 / g)^-1
+            // modulo m2 / g.
+            let reduced = m2 / g;
+            let t = mul_mod(
+                difference / g,
+                mod_inverse(m1 / g, reduced).unwrap(),
+                reduced,
+            );
+            let modulus = lcm(m1, m2)?;
+            Some((add_mod(r1, mul_mod(m1, t, modulus), modulus), modulus))
+        })
+}
+
+// The smallest `x >= 0` with `base^x` congruent to `target` modulo `m`, by baby-step giant-step.
+// `base` and `m` must be coprime. This takes time and memory proportional to the square root of
+// `m`.
+pub fn discrete_log(base: i128, target: i128, m: i128) -> Option<u128> {
+    let target = target.rem_euclid(m);
+    let steps = (m as f64).sqrt().ceil() as u128 + 1;
+
+    // The first exponent below `steps` that gives each power of `base`.
+    let mut baby_steps = HashMap::new();
+    let mut power = 1 % m;
+    for j in 0..steps {
+        baby_steps.entry(power).or_insert(j);
+        power = mul_mod(power, base, m);
+    }
+
+    // Look for target * base^(-i * steps) among the baby steps.
+    let giant_step = mod_pow(mod_inverse(base, m)?, steps, m);
+    let mut value = target;
+    for i in 0..steps {
+        if let Some(&j) = baby_steps.get(&value) {
+            return Some(i * steps + j);
+        }
+        value = mul_mod(value, giant_step, m);
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        crt, discrete_log, extended_gcd, gcd, lcm, lcm_all, mod_inverse, mod_pow, mul_mod,
+    };
+
+    #[test]
+    fn check_gcd_and_lcm() {
+        assert_eq!(
+            (gcd(12, 18), gcd(-12, 18), gcd(0, 5), gcd(0, 0)),
+            (6, 6, 5, 0)
+        );
+        let (g, x, y) = extended_gcd(240, 46);
+        assert_eq!((g, 240 * x + 46 * y), (2, 2));
+        let (g, x, y) = extended_gcd(-7, 3);
+        assert_eq!((g, -7 * x + 3 * y), (1, 1));
+        assert_eq!(lcm(4, -6), Some(12));
+        assert_eq!(lcm_all([2, 3, 4, 5, 6]), Some(60));
+        assert_eq!(lcm_all([]), Some(1));
+        assert_eq!(lcm(i128::MAX, i128::MAX - 1), None);
+    }
+
+    #[test]
+    fn check_modular_arithmetic() {
+        assert_eq!(mod_pow(2, 10, 1_000), 24);
+        assert_eq!(mod_pow(-2, 3, 7), 6);
+        assert_eq!(mod_pow(5, 0, 1), 0);
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(-3, 11), Some(7));
+        assert_eq!(mod_inverse(6, 9), None);
+
+        // Big enough that the product overflows an i128.
+        let m = i128::MAX - 1;
+        assert_eq!(mul_mod(m - 1, m - 1, m), 1);
+        assert_eq!(mul_mod(m - 1, 2, m), m - 2);
+        // 2^127 - 1 is prime, so Fermat's little theorem applies.
+        let p = i128::MAX;
+        assert_eq!(mod_pow(3, (p - 1) as u128, p), 1);
+    }
+
+    #[test]
+    fn check_crt() {
+        // The buses from 2020 day 13: 7,13,x,x,59,x,31,19 leave at t + offset.
+        let buses = [(7, 0), (13, 1), (59, 4), (31, 6), (19, 7)];
+        let (t, modulus) = crt(buses.iter().map(|&(bus, offset)| (-offset, bus))).unwrap();
+        assert_eq!((t, modulus), (1_068_781, 7 * 13 * 59 * 31 * 19));
+
+        // Moduli that share factors.
+        assert_eq!(crt([(2, 6), (8, 10)]), Some((8, 30)));
+        assert_eq!(crt([(1, 6), (2, 4)]), None);
+        assert_eq!(crt([]), Some((0, 1)));
+    }
+
+    #[test]
+    fn check_discrete_log() {
+        // The card's public key from 2020 day 25 has a loop size of 8.
+        assert_eq!(discrete_log(7, 5_764_801, 20_201_227), Some(8));
+        assert_eq!(discrete_log(7, 17_807_724, 20_201_227), Some(11));
+        assert_eq!(discrete_log(2, 1, 11), Some(0));
+        // The powers of 4 modulo 7 are 1, 4 and 2, so 3 is never reached.
+        assert_eq!(discrete_log(4, 3, 7), None);
+    }
+}
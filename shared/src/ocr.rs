@@ -0,0 +1,117 @@
+// Reading the block letters that some puzzles draw as their answer.
+//
+// The letters are 10 pixels tall and 6 wide, as in 2018 day 10, and are separated by at least
+// one empty column. Only the letters that have turned up in puzzles are known.
+use crate::grid::Grid;
+
+const LETTER_HEIGHT: usize = 10;
+
+#[rustfmt::skip]
+const LARGE_FONT: [(char, [&str; LETTER_HEIGHT]); 15] = [
+    ('A', ["..##..", ".#..#.", "#....#", "#....#", "#....#", "######", "#....#", "#....#", "#....#", "#....#"]),
+    ('B', ["#####.", "#....#", "#....#", "#....#", "#####.", "#....#", "#....#", "#....#", "#....#", "#####."]),
+    ('C', [".####.", "#....#", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#....#", ".####."]),
+    ('E', ["######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....", "#.....", "######"]),
+    ('F', ["######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....", "#.....", "#....."]),
+    ('G', [".####.", "#....#", "#.....", "#.....", "#.....", "#..###", "#....#", "#....#", "#...##", ".###.#"]),
+    ('H', ["#....#", "#....#", "#....#", "#....#", "######", "#....#", "#....#", "#....#", "#....#", "#....#"]),
+    ('J', ["...###", "....#.", "....#.", "....#.", "....#.", "....#.", "....#.", "#...#.", "#...#.", ".###.."]),
+    ('K', ["#....#", "#...#.", "#..#..", "#.#...", "##....", "##....", "#.#...", "#..#..", "#...#.", "#....#"]),
+    ('L', ["#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "######"]),
+    ('N', ["#....#", "##...#", "##...#", "#.#..#", "#.#..#", "#..#.#", "#..#.#", "#...##", "#...##", "#....#"]),
+    ('P', ["#####.", "#....#", "#....#", "#....#", "#####.", "#.....", "#.....", "#.....", "#.....", "#....."]),
+    ('R', ["#####.", "#....#", "#....#", "#....#", "#####.", "#..#..", "#...#.", "#...#.", "#....#", "#....#"]),
+    ('X', ["#....#", "#....#", ".#..#.", ".#..#.", "..##..", "..##..", ".#..#.", ".#..#.", "#....#", "#....#"]),
+    ('Z', ["######", ".....#", ".....#", "....#.", "...#..", "..#...", ".#....", "#.....", "#.....", "######"]),
+];
+
+// The letters drawn by the lit cells of `grid`, reading left to right, or `None` if the grid isn't
+// the height of a letter or any of the shapes aren't known letters. Empty rows and columns around
+// the letters are ignored.
+pub fn read_letters(grid: &Grid<bool>) -> Option<String> {
+    let lit_rows: Vec<usize> = (0..grid.height())
+        .filter(|&y| grid.row(y).iter().any(|&lit| lit))
+        .collect();
+    let (&top, &bottom) = (lit_rows.first()?, lit_rows.last()?);
+    if bottom - top + 1 != LETTER_HEIGHT {
+        return None;
+    }
+
+    // Split the columns into runs that have something lit in them.
+    let mut letters = String::new();
+    let mut x = 0;
+    while x < grid.width() {
+        let is_empty = |x: usize| grid.column(x).all(|&lit| !lit);
+        if is_empty(x) {
+            x += 1;
+            continue;
+        }
+        let start = x;
+        while x < grid.width() && !is_empty(x) {
+            x += 1;
+        }
+        let shape: Vec<String> = (top..=bottom)
+            .map(|y| {
+                grid.row(y)[start..x]
+                    .iter()
+                    .map(|&lit| if lit { '#' } else { '.' })
+                    .collect()
+            })
+            .collect();
+        let &(letter, _) = LARGE_FONT
+            .iter()
+            .find(|(_, glyph)| glyph.iter().eq(shape.iter()))?;
+        letters.push(letter);
+    }
+    Some(letters)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{read_letters, LARGE_FONT};
+    use crate::grid::Grid;
+
+    // Draw `text` in the large font, with two empty columns between letters and a border of
+    // `margin` empty cells.
+    fn draw(text: &str, margin: usize) -> Grid<bool> {
+        let glyphs: Vec<&[&str; 10]> = text
+            .chars()
+            .map(|c| {
+                &LARGE_FONT
+                    .iter()
+                    .find(|&&(letter, _)| letter == c)
+                    .unwrap()
+                    .1
+            })
+            .collect();
+        let width = 8 * glyphs.len() - 2 + 2 * margin;
+        Grid::from_fn(width, 10 + 2 * margin, |x, y| {
+            let (x, y) = (x.wrapping_sub(margin), y.wrapping_sub(margin));
+            y < 10
+                && x % 8 < 6
+                && glyphs
+                    .get(x / 8)
+                    .is_some_and(|glyph| glyph[y].as_bytes()[x % 8] == b'#')
+        })
+    }
+
+    #[test]
+    fn check_read_letters() {
+        let alphabet: String = LARGE_FONT.iter().map(|&(letter, _)| letter).collect();
+        assert_eq!(read_letters(&draw(&alphabet, 0)), Some(alphabet));
+        assert_eq!(read_letters(&draw("ZAP", 3)).as_deref(), Some("ZAP"));
+    }
+
+    #[test]
+    fn check_unreadable() {
+        // Too short, since the top row is cut off.
+        let grid = draw("HE", 0);
+        let cut = Grid::from_fn(grid.width(), 9, |x, y| grid[(x, y + 1)]);
+        assert_eq!(read_letters(&cut), None);
+        // An unknown shape.
+        let mut grid = draw("BE", 0);
+        grid[(0, 0)] = false;
+        assert_eq!(read_letters(&grid), None);
+        assert_eq!(read_letters(&Grid::new(4, 4, false)), None);
+    }
+}
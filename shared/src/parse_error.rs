@@ -0,0 +1,221 @@
+// A parse error that points at the offending text in a puzzle input.
+//
+// A `FromStr` impl only sees one line, so it reports the column, what it expected and what it
+// found. The caller adds the block and line numbers with `at`, or by parsing with `parse_lines`.
+// Indices are stored 0-based and displayed 1-based, and the error renders like:
+//
+//   error: expected an integer, found "x1"
+//     --> block 1, line 3, column 8
+//     |
+//   3 | 1-3 a: x1
+//     |        ^^
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParseError {
+    pub block: Option<usize>,
+    pub line: Option<usize>,
+    // Counted in characters rather than bytes, so that the caret lines up.
+    pub column: usize,
+    pub expected: String,
+    pub found: String,
+    // The whole line that was being parsed.
+    pub input: String,
+}
+
+impl ParseError {
+    // `found` should be a slice of `input`, which is used to work out the column. Pass an empty
+    // slice from the end of `input` if the line ended too soon.
+    pub fn new(input: &str, found: &str, expected: impl Into<String>) -> Self {
+        ParseError {
+            block: None,
+            line: None,
+            column: input[..offset_in(input, found)].chars().count(),
+            expected: expected.into(),
+            found: found.to_string(),
+            input: input.to_string(),
+        }
+    }
+
+    // Record which line of which block of the input this error came from.
+    pub fn at(self, block: usize, line: usize) -> Self {
+        ParseError {
+            block: Some(block),
+            line: Some(line),
+            ..self
+        }
+    }
+}
+
+// The byte offset of `slice` within `input`, or 0 if it isn't a slice of `input`.
+fn offset_in(input: &str, slice: &str) -> usize {
+    let offset = (slice.as_ptr() as usize).wrapping_sub(input.as_ptr() as usize);
+    match offset.checked_add(slice.len()) {
+        Some(end) if end <= input.len() => offset,
+        _ => 0,
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.found.is_empty() {
+            writeln!(f, "error: expected {}, found end of line", self.expected)?;
+        } else {
+            writeln!(
+                f,
+                "error: expected {}, found {:?}",
+                self.expected, self.found
+            )?;
+        }
+
+        let mut location = Vec::new();
+        if let Some(block) = self.block {
+            location.push(format!("block {}", block + 1));
+        }
+        if let Some(line) = self.line {
+            location.push(format!("line {}", line + 1));
+        }
+        location.push(format!("column {}", self.column + 1));
+
+        let line_number = self
+            .line
+            .map_or(String::new(), |line| (line + 1).to_string());
+        let gutter = " ".repeat(line_number.len());
+        writeln!(f, "{} --> {}", gutter, location.join(", "))?;
+        writeln!(f, "{} |", gutter)?;
+        writeln!(f, "{} | {}", line_number, self.input)?;
+        write!(
+            f,
+            "{} | {}{}",
+            gutter,
+            " ".repeat(self.column),
+            "^".repeat(self.found.chars().count().max(1))
+        )
+    }
+}
+
+impl Error for ParseError {}
+
+// Parse `token`, a slice of `input`, describing it as `expected` if it doesn't parse.
+pub fn parse_token<T: FromStr>(input: &str, token: &str, expected: &str) -> Result<T, ParseError> {
+    token
+        .parse::<T>()
+        .map_err(|_| ParseError::new(input, token, expected))
+}
+
+// Strip `prefix` from `rest`, a slice of `input`.
+pub fn expect_prefix<'a>(input: &str, rest: &'a str, prefix: &str) -> Result<&'a str, ParseError> {
+    rest.strip_prefix(prefix).ok_or_else(|| {
+        let found_len = rest
+            .char_indices()
+            .nth(prefix.chars().count())
+            .map_or(rest.len(), |(idx, _)| idx);
+        ParseError::new(input, &rest[..found_len], format!("{:?}", prefix))
+    })
+}
+
+// Split `rest`, a slice of `input`, around the first `delimiter`.
+pub fn split_once<'a>(
+    input: &str,
+    rest: &'a str,
+    delimiter: &str,
+) -> Result<(&'a str, &'a str), ParseError> {
+    rest.split_once(delimiter)
+        .ok_or_else(|| ParseError::new(input, &rest[rest.len()..], format!("{:?}", delimiter)))
+}
+
+// Parse every line of `input_lines[block]`, recording where any error came from.
+pub fn parse_lines<T>(input_lines: &[Vec<String>], block: usize) -> Result<Vec<T>, ParseError>
+where
+    T: FromStr<Err = ParseError>,
+{
+    input_lines[block]
+        .iter()
+        .enumerate()
+        .map(|(line, s)| s.parse::<T>().map_err(|error| error.at(block, line)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{expect_prefix, parse_lines, parse_token, split_once, ParseError};
+
+    #[test]
+    fn check_error_renders_snippet() {
+        let input = "1-3 a: x1";
+        let error = parse_token::<u32>(input, &input[7..], "an integer")
+            .unwrap_err()
+            .at(0, 2);
+        assert_eq!(
+            error.to_string(),
+            "error: expected an integer, found \"x1\"
+  --> block 1, line 3, column 8
+  |
+3 | 1-3 a: x1
+  |        ^^"
+        );
+    }
+
+    #[test]
+    fn check_column_counts_characters() {
+        let input = "é€ 12x";
+        let error = parse_token::<u32>(input, &input[6..], "an integer").unwrap_err();
+        assert_eq!(error.column, 3);
+        assert!(error.to_string().ends_with("\n | é€ 12x\n |    ^^^"));
+    }
+
+    #[test]
+    fn check_found_outside_input() {
+        // Text from before the input, e.g. an earlier part of the same line, points at the start.
+        let line = "Game 1: 3 blue";
+        let error = ParseError::new(&line[8..], &line[..4], "a count");
+        assert_eq!((error.column, error.found.as_str()), (0, "Game"));
+        let error = ParseError::new(&line[..4], &line[8..], "a count");
+        assert_eq!(error.column, 0);
+    }
+
+    #[test]
+    fn check_missing_text_is_end_of_line() {
+        let input = "Game 1";
+        let error = split_once(input, input, ": ").unwrap_err();
+        assert_eq!(error.column, 6);
+        assert_eq!(error.expected, "\": \"");
+        assert!(error
+            .to_string()
+            .starts_with("error: expected \": \", found end of line"));
+        assert!(error.to_string().ends_with("|       ^"));
+    }
+
+    #[test]
+    fn check_expect_prefix() {
+        let input = "Card 1: 2";
+        assert_eq!(expect_prefix(input, input, "Card "), Ok("1: 2"));
+        let error = expect_prefix(input, input, "Game ").unwrap_err();
+        assert_eq!((error.column, error.found.as_str()), (0, "Card "));
+    }
+
+    #[test]
+    fn check_parse_lines_records_position() {
+        let input_lines = vec![
+            vec!["1".to_string()],
+            vec!["2".to_string(), "three".to_string()],
+        ];
+        assert_eq!(parse_lines::<Number>(&input_lines, 0), Ok(vec![Number(1)]));
+        let error = parse_lines::<Number>(&input_lines, 1).unwrap_err();
+        assert_eq!((error.block, error.line), (Some(1), Some(1)));
+        assert_eq!(error.found, "three");
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Number(u32);
+
+    impl std::str::FromStr for Number {
+        type Err = ParseError;
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            parse_token(s, s, "an integer").map(Number)
+        }
+    }
+}
@@ -0,0 +1,513 @@
+// Small parser combinators for puzzle input lines, which fail with a `ParseError` pointing at
+// the offending text.
+//
+// A `Cursor` walks along a line:
+//
+//   let star = parse_line(s, |cursor| {
+//       cursor.literal("position=<")?;
+//       let x = cursor.signed_integer::<i32>()?;
+//       ...
+//   })?;
+//
+// Numbers and words skip any spaces in front of them, as with `scanf`, so "Card  12" matches
+// `literal("Card ")` followed by `integer()`.
+//
+// For lines that are just fields between fixed text, `pattern!` writes the `FromStr` impl of a
+// struct from a pattern such as "Card {id}: {winners} | {numbers}".
+use crate::parse_error::ParseError;
+use std::collections::HashSet;
+use std::hash::Hash;
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug)]
+pub struct Cursor<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    pub fn new(input: &'a str) -> Self {
+        Cursor { input, pos: 0 }
+    }
+
+    // The part of the line that hasn't been parsed yet.
+    pub fn rest(&self) -> &'a str {
+        &self.input[self.pos..]
+    }
+
+    pub fn is_at_end(&self) -> bool {
+        self.pos == self.input.len()
+    }
+
+    // An error about the next `char_count` characters of the line.
+    pub fn error(&self, char_count: usize, expected: impl Into<String>) -> ParseError {
+        let rest = self.rest();
+        let len = rest
+            .char_indices()
+            .nth(char_count)
+            .map_or(rest.len(), |(idx, _)| idx);
+        ParseError::new(self.input, &rest[..len], expected)
+    }
+
+    // Consume characters while `predicate` holds, returning them.
+    pub fn take_while(&mut self, predicate: impl Fn(char) -> bool) -> &'a str {
+        let rest = self.rest();
+        let len = rest
+            .char_indices()
+            .find(|&(_, c)| !predicate(c))
+            .map_or(rest.len(), |(idx, _)| idx);
+        self.pos += len;
+        &rest[..len]
+    }
+
+    pub fn spaces(&mut self) {
+        self.take_while(|c| c == ' ');
+    }
+
+    pub fn literal(&mut self, text: &str) -> Result<(), ParseError> {
+        if self.rest().starts_with(text) {
+            self.pos += text.len();
+            Ok(())
+        } else {
+            Err(self.error(text.chars().count(), format!("{:?}", text)))
+        }
+    }
+
+    // Parse a run of digits.
+    pub fn integer<T: FromStr>(&mut self) -> Result<T, ParseError> {
+        let start = *self;
+        self.spaces();
+        let token_start = *self;
+        let digits = self.take_while(|c| c.is_ascii_digit());
+        digits.parse::<T>().map_err(|_| {
+            *self = start;
+            token_start.error(digits.len().max(1), "an integer")
+        })
+    }
+
+    // Parse a run of digits, optionally starting with a sign.
+    pub fn signed_integer<T: FromStr>(&mut self) -> Result<T, ParseError> {
+        let start = *self;
+        self.spaces();
+        let token_start = *self;
+        let sign = usize::from(self.rest().starts_with(['-', '+']));
+        self.pos += sign;
+        let digits = self.take_while(|c| c.is_ascii_digit());
+        let token = &token_start.rest()[..sign + digits.len()];
+        token.parse::<T>().map_err(|_| {
+            *self = start;
+            token_start.error(token.len().max(1), "a signed integer")
+        })
+    }
+
+    // Parse a run of letters and digits.
+    pub fn word(&mut self) -> Result<&'a str, ParseError> {
+        let start = *self;
+        self.spaces();
+        match self.take_while(|c| c.is_alphanumeric()) {
+            "" => {
+                let error = self.error(1, "a word");
+                *self = start;
+                Err(error)
+            }
+            word => Ok(word),
+        }
+    }
+
+    pub fn char(&mut self) -> Result<char, ParseError> {
+        let c = self
+            .rest()
+            .chars()
+            .next()
+            .ok_or_else(|| self.error(0, "a character"))?;
+        self.pos += c.len_utf8();
+        Ok(c)
+    }
+
+    pub fn end(&self) -> Result<(), ParseError> {
+        if self.is_at_end() {
+            Ok(())
+        } else {
+            Err(self.error(usize::MAX, "end of line"))
+        }
+    }
+
+    // Run `parser`, leaving the cursor where it was if it fails.
+    pub fn attempt<T>(
+        &mut self,
+        parser: impl FnOnce(&mut Self) -> Result<T, ParseError>,
+    ) -> Result<T, ParseError> {
+        let start = *self;
+        parser(self).inspect_err(|_| *self = start)
+    }
+
+    // Parse as many items as possible, each one after the first preceded by `separator`.
+    // This stops before a separator that isn't followed by an item, so an empty list is allowed.
+    pub fn separated<T>(
+        &mut self,
+        item: impl Fn(&mut Self) -> Result<T, ParseError>,
+        separator: &str,
+    ) -> Vec<T> {
+        match self.attempt(&item) {
+            Ok(first) => self.continue_list(first, item, separator),
+            Err(_) => Vec::new(),
+        }
+    }
+
+    // Like `separated`, but there must be at least one item.
+    pub fn separated1<T>(
+        &mut self,
+        item: impl Fn(&mut Self) -> Result<T, ParseError>,
+        separator: &str,
+    ) -> Result<Vec<T>, ParseError> {
+        let first = self.attempt(&item)?;
+        Ok(self.continue_list(first, item, separator))
+    }
+
+    fn continue_list<T>(
+        &mut self,
+        first: T,
+        item: impl Fn(&mut Self) -> Result<T, ParseError>,
+        separator: &str,
+    ) -> Vec<T> {
+        let mut items = vec![first];
+        while let Ok(next) = self.attempt(|cursor| {
+            cursor.literal(separator)?;
+            item(cursor)
+        }) {
+            items.push(next);
+        }
+        items
+    }
+
+    // Parse a key, `separator` and then a value, like "hgt:183cm".
+    pub fn key_value<K, V>(
+        &mut self,
+        key: impl FnOnce(&mut Self) -> Result<K, ParseError>,
+        separator: &str,
+        value: impl FnOnce(&mut Self) -> Result<V, ParseError>,
+    ) -> Result<(K, V), ParseError> {
+        let key = key(self)?;
+        self.literal(separator)?;
+        Ok((key, value(self)?))
+    }
+}
+
+// Run `parser` over the whole of `input`, failing if any of it is left over.
+pub fn parse_line<'a, T>(
+    input: &'a str,
+    parser: impl FnOnce(&mut Cursor<'a>) -> Result<T, ParseError>,
+) -> Result<T, ParseError> {
+    let mut cursor = Cursor::new(input);
+    let parsed = parser(&mut cursor)?;
+    cursor.end()?;
+    Ok(parsed)
+}
+
+// Parse `input_lines[block]` as a grid with the same number of cells in every row.
+// `cell` converts each character, returning `None` if it isn't allowed.
+pub fn fixed_width_grid<T>(
+    input_lines: &[Vec<String>],
+    block: usize,
+    cell: impl Fn(char) -> Option<T>,
+    expected: &str,
+) -> Result<Vec<Vec<T>>, ParseError> {
+    let mut width = None;
+    let mut grid = Vec::new();
+    for (line, s) in input_lines[block].iter().enumerate() {
+        let mut cursor = Cursor::new(s);
+        let mut row = Vec::new();
+        while !cursor.is_at_end() && Some(row.len()) != width {
+            let start = cursor;
+            let c = cursor.char()?;
+            row.push(cell(c).ok_or_else(|| start.error(1, expected).at(block, line))?);
+        }
+        match width {
+            Some(width) if row.len() < width => {
+                return Err(cursor.error(0, format!("{} cells", width)).at(block, line));
+            }
+            Some(_) => cursor.end().map_err(|error| error.at(block, line))?,
+            None => width = Some(row.len()),
+        }
+        grid.push(row);
+    }
+    Ok(grid)
+}
+
+// A value that can fill a `{field}` in a `pattern!`.
+pub trait Field: Sized {
+    fn parse_field(cursor: &mut Cursor) -> Result<Self, ParseError>;
+}
+
+macro_rules! unsigned_fields {
+    ($($ty:ty),*) => {
+        $(impl Field for $ty {
+            fn parse_field(cursor: &mut Cursor) -> Result<Self, ParseError> {
+                cursor.integer()
+            }
+        })*
+    };
+}
+
+macro_rules! signed_fields {
+    ($($ty:ty),*) => {
+        $(impl Field for $ty {
+            fn parse_field(cursor: &mut Cursor) -> Result<Self, ParseError> {
+                cursor.signed_integer()
+            }
+        })*
+    };
+}
+
+unsigned_fields!(u8, u16, u32, u64, u128, usize);
+signed_fields!(i8, i16, i32, i64, i128, isize);
+
+impl Field for char {
+    fn parse_field(cursor: &mut Cursor) -> Result<Self, ParseError> {
+        cursor.char()
+    }
+}
+
+impl Field for String {
+    fn parse_field(cursor: &mut Cursor) -> Result<Self, ParseError> {
+        cursor.word().map(str::to_string)
+    }
+}
+
+// Lists are separated by spaces.
+impl<T: Field> Field for Vec<T> {
+    fn parse_field(cursor: &mut Cursor) -> Result<Self, ParseError> {
+        Ok(cursor.separated(T::parse_field, " "))
+    }
+}
+
+impl<T: Field + Eq + Hash> Field for HashSet<T> {
+    fn parse_field(cursor: &mut Cursor) -> Result<Self, ParseError> {
+        Ok(cursor.separated(T::parse_field, " ").into_iter().collect())
+    }
+}
+
+// A piece of a `pattern!`: either text to match exactly or the name of a field.
+#[derive(Debug, PartialEq)]
+pub enum Piece<'p> {
+    Literal(&'p str),
+    Field(&'p str),
+}
+
+pub fn pattern_pieces(pattern: &str) -> Vec<Piece<'_>> {
+    let mut pieces = Vec::new();
+    let mut rest = pattern;
+    while let Some(open) = rest.find('{') {
+        let close = open + rest[open..].find('}').expect("Unclosed '{' in pattern.");
+        if open > 0 {
+            pieces.push(Piece::Literal(&rest[..open]));
+        }
+        pieces.push(Piece::Field(&rest[open + 1..close]));
+        rest = &rest[close + 1..];
+    }
+    if !rest.is_empty() {
+        pieces.push(Piece::Literal(rest));
+    }
+    pieces
+}
+
+// Define a struct along with a `FromStr` impl that matches lines against a pattern, e.g.
+//
+//   pattern! {
+//       #[pattern = "Card {id}: {winners} | {numbers}"]
+//       #[derive(Debug)]
+//       pub(crate) struct ScratchCard {
+//           id: u32,
+//           winners: HashSet<u32>,
+//           numbers: Vec<u32>,
+//       }
+//   }
+//
+// Every field must appear in the pattern, and have a type that implements `Field`.
+#[macro_export]
+macro_rules! pattern {
+    (
+        #[pattern = $pattern:literal]
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident {
+            $($field_vis:vis $field:ident : $ty:ty),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        $vis struct $name {
+            $($field_vis $field: $ty),*
+        }
+
+        impl ::std::str::FromStr for $name {
+            type Err = $crate::parse_error::ParseError;
+
+            fn from_str(s: &str) -> ::std::result::Result<Self, Self::Err> {
+                use $crate::parser::{parse_line, pattern_pieces, Field, Piece};
+                $(let mut $field: Option<$ty> = None;)*
+                parse_line(s, |cursor| {
+                    for piece in pattern_pieces($pattern) {
+                        match piece {
+                            Piece::Literal(text) => cursor.literal(text)?,
+                            $(Piece::Field(stringify!($field)) => {
+                                $field = Some(<$ty as Field>::parse_field(cursor)?)
+                            })*
+                            Piece::Field(other) => {
+                                panic!("{{{}}} in {:?} isn't a field of {}.", other, $pattern, stringify!($name))
+                            }
+                        }
+                    }
+                    Ok(())
+                })?;
+                Ok($name {
+                    $($field: $field.unwrap_or_else(|| {
+                        panic!("{:?} is missing {{{}}}.", $pattern, stringify!($field))
+                    })),*
+                })
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fixed_width_grid, parse_line, pattern_pieces, Cursor, Piece};
+    use std::collections::HashSet;
+
+    #[test]
+    fn check_numbers() {
+        let mut cursor = Cursor::new("12  -7 +3 x");
+        assert_eq!(cursor.integer::<u32>(), Ok(12));
+        assert!(cursor.integer::<u32>().is_err());
+        assert_eq!(cursor.signed_integer::<i32>(), Ok(-7));
+        assert_eq!(cursor.signed_integer::<i32>(), Ok(3));
+        let error = cursor.signed_integer::<i32>().unwrap_err();
+        assert_eq!((error.column, error.found.as_str()), (10, "x"));
+        assert_eq!(cursor.rest(), " x");
+    }
+
+    #[test]
+    fn check_integer_overflow_is_an_error() {
+        let error = parse_line("300", |cursor| cursor.integer::<u8>()).unwrap_err();
+        assert_eq!(
+            (error.found.as_str(), error.expected.as_str()),
+            ("300", "an integer")
+        );
+    }
+
+    #[test]
+    fn check_star() {
+        let star = parse_line("position=< 9,  1> velocity=< 0, -2>", |cursor| {
+            cursor.literal("position=<")?;
+            let x = cursor.signed_integer::<i32>()?;
+            cursor.literal(",")?;
+            let y = cursor.signed_integer::<i32>()?;
+            cursor.literal("> velocity=<")?;
+            let dx = cursor.signed_integer::<i32>()?;
+            cursor.literal(",")?;
+            let dy = cursor.signed_integer::<i32>()?;
+            cursor.literal(">")?;
+            Ok((x, y, dx, dy))
+        });
+        assert_eq!(star, Ok((9, 1, 0, -2)));
+    }
+
+    #[test]
+    fn check_separated() {
+        let rounds = parse_line("3 blue, 4 red; 1 red", |cursor| {
+            cursor.separated1(
+                |cursor| {
+                    cursor.separated1(
+                        |cursor| {
+                            let count = cursor.integer::<u32>()?;
+                            Ok((count, cursor.word()?))
+                        },
+                        ", ",
+                    )
+                },
+                "; ",
+            )
+        });
+        assert_eq!(
+            rounds,
+            Ok(vec![vec![(3, "blue"), (4, "red")], vec![(1, "red")]])
+        );
+        let mut cursor = Cursor::new("1 2 | 3");
+        assert_eq!(
+            cursor.separated(|cursor| cursor.integer::<u8>(), " "),
+            vec![1, 2]
+        );
+        assert_eq!(cursor.rest(), " | 3");
+    }
+
+    #[test]
+    fn check_key_value() {
+        let fields = parse_line("hgt:183cm ecl:gry", |cursor| {
+            cursor.separated1(
+                |cursor| cursor.key_value(|c| c.word(), ":", |c| c.word()),
+                " ",
+            )
+        });
+        assert_eq!(fields, Ok(vec![("hgt", "183cm"), ("ecl", "gry")]));
+    }
+
+    #[test]
+    fn check_fixed_width_grid() {
+        let lines = |rows: &[&str]| vec![rows.iter().map(|row| row.to_string()).collect()];
+        let is_tree = |c| match c {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        };
+        assert_eq!(
+            fixed_width_grid(&lines(&["#.", ".#"]), 0, is_tree, "'#' or '.'"),
+            Ok(vec![vec![true, false], vec![false, true]])
+        );
+        let error = fixed_width_grid(&lines(&["#.", "#"]), 0, is_tree, "").unwrap_err();
+        assert_eq!((error.line, error.column), (Some(1), 1));
+        let error = fixed_width_grid(&lines(&["#.", "#.#"]), 0, is_tree, "").unwrap_err();
+        assert_eq!((error.line, error.found.as_str()), (Some(1), "#"));
+        let error = fixed_width_grid(&lines(&["#.", "x."]), 0, is_tree, "'#' or '.'").unwrap_err();
+        assert_eq!((error.line, error.column), (Some(1), 0));
+    }
+
+    #[test]
+    fn check_pattern_pieces() {
+        assert_eq!(
+            pattern_pieces("#{id} @ {x},{y}"),
+            vec![
+                Piece::Literal("#"),
+                Piece::Field("id"),
+                Piece::Literal(" @ "),
+                Piece::Field("x"),
+                Piece::Literal(","),
+                Piece::Field("y"),
+            ]
+        );
+    }
+
+    crate::pattern! {
+        #[pattern = "Card {id}: {winners} | {numbers}"]
+        #[derive(Debug, PartialEq)]
+        struct ScratchCard {
+            id: u32,
+            winners: HashSet<u32>,
+            numbers: Vec<u32>,
+        }
+    }
+
+    #[test]
+    fn check_pattern() {
+        assert_eq!(
+            "Card  3:  1 21 | 69  1 21".parse::<ScratchCard>(),
+            Ok(ScratchCard {
+                id: 3,
+                winners: HashSet::from([1, 21]),
+                numbers: vec![69, 1, 21],
+            })
+        );
+        let error = "Card 3: 1 21 / 69".parse::<ScratchCard>().unwrap_err();
+        assert_eq!((error.column, error.expected.as_str()), (12, "\" | \""));
+        let error = "Card x: 1 | 2".parse::<ScratchCard>().unwrap_err();
+        assert_eq!((error.column, error.found.as_str()), (5, "x"));
+    }
+}
@@ -0,0 +1,412 @@
+// Points (or vectors) on an N-dimensional integer lattice, with directions and turns for 2D.
+//
+// In 2D, y increases downwards as it does in a `Grid`, so `Direction::North` is (0, -1).
+use crate::parse_error::ParseError;
+use crate::parser::parse_line;
+use std::fmt;
+use std::ops::{Add, AddAssign, Index, IndexMut, Mul, Neg, Sub, SubAssign};
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Point<const N: usize>(pub [i64; N]);
+
+pub type Point2 = Point<2>;
+pub type Point3 = Point<3>;
+pub type Point4 = Point<4>;
+
+impl<const N: usize> Point<N> {
+    pub const ORIGIN: Self = Point([0; N]);
+
+    pub fn x(&self) -> i64 {
+        self.0[0]
+    }
+
+    pub fn y(&self) -> i64 {
+        self.0[1]
+    }
+
+    pub fn z(&self) -> i64 {
+        self.0[2]
+    }
+
+    pub fn w(&self) -> i64 {
+        self.0[3]
+    }
+
+    // The Manhattan (taxicab) distance from the origin.
+    pub fn manhattan(&self) -> i64 {
+        self.0.iter().map(|c| c.abs()).sum()
+    }
+
+    pub fn manhattan_distance(&self, other: &Self) -> i64 {
+        (*self - *other).manhattan()
+    }
+
+    // The largest difference in any one coordinate, i.e. the number of king's moves apart.
+    pub fn chebyshev_distance(&self, other: &Self) -> i64 {
+        (*self - *other)
+            .0
+            .iter()
+            .map(|c| c.abs())
+            .max()
+            .unwrap_or(0)
+    }
+
+    // The 2N points one step away along an axis.
+    pub fn neighbours(&self) -> impl Iterator<Item = Self> + '_ {
+        (0..N).flat_map(move |axis| {
+            [-1, 1].into_iter().map(move |step| {
+                let mut neighbour = *self;
+                neighbour.0[axis] += step;
+                neighbour
+            })
+        })
+    }
+
+    // The 3^N - 1 points that touch this one, including diagonally.
+    pub fn all_neighbours(&self) -> impl Iterator<Item = Self> + '_ {
+        (0..3_usize.pow(N as u32))
+            .map(move |mut digits| {
+                let mut neighbour = *self;
+                for coordinate in neighbour.0.iter_mut() {
+                    *coordinate += (digits % 3) as i64 - 1;
+                    digits /= 3;
+                }
+                neighbour
+            })
+            .filter(move |neighbour| neighbour != self)
+    }
+
+    pub fn signum(&self) -> Self {
+        Point(self.0.map(i64::signum))
+    }
+}
+
+impl Point<2> {
+    // A quarter turn clockwise about the origin (as seen with y increasing downwards).
+    pub fn rotate_right(&self) -> Self {
+        Point([-self.y(), self.x()])
+    }
+
+    // A quarter turn anticlockwise about the origin.
+    pub fn rotate_left(&self) -> Self {
+        Point([self.y(), -self.x()])
+    }
+}
+
+impl Point<3> {
+    // Quarter turns about each axis, using the right-hand rule.
+    pub fn rotate_x(&self) -> Self {
+        Point([self.x(), -self.z(), self.y()])
+    }
+
+    pub fn rotate_y(&self) -> Self {
+        Point([self.z(), self.y(), -self.x()])
+    }
+
+    pub fn rotate_z(&self) -> Self {
+        Point([-self.y(), self.x(), self.z()])
+    }
+
+    // The 24 ways of orienting this point by rotating about the axes.
+    pub fn orientations(&self) -> Vec<Self> {
+        // Face each of the six directions, then spin about the x axis.
+        let mut facings = Vec::with_capacity(6);
+        let mut facing = *self;
+        for _ in 0..4 {
+            facings.push(facing);
+            facing = facing.rotate_z();
+        }
+        facings.push(self.rotate_y());
+        facings.push(self.rotate_y().rotate_y().rotate_y());
+        facings
+            .into_iter()
+            .flat_map(|facing| {
+                (0..4).scan(facing, |point, _| {
+                    let current = *point;
+                    *point = point.rotate_x();
+                    Some(current)
+                })
+            })
+            .collect()
+    }
+}
+
+impl<const N: usize> Add for Point<N> {
+    type Output = Self;
+
+    fn add(mut self, other: Self) -> Self {
+        self += other;
+        self
+    }
+}
+
+impl<const N: usize> AddAssign for Point<N> {
+    fn add_assign(&mut self, other: Self) {
+        for (coordinate, other) in self.0.iter_mut().zip(other.0) {
+            *coordinate += other;
+        }
+    }
+}
+
+impl<const N: usize> Sub for Point<N> {
+    type Output = Self;
+
+    fn sub(mut self, other: Self) -> Self {
+        self -= other;
+        self
+    }
+}
+
+impl<const N: usize> SubAssign for Point<N> {
+    fn sub_assign(&mut self, other: Self) {
+        for (coordinate, other) in self.0.iter_mut().zip(other.0) {
+            *coordinate -= other;
+        }
+    }
+}
+
+impl<const N: usize> Neg for Point<N> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Point(self.0.map(|c| -c))
+    }
+}
+
+impl<const N: usize> Mul<i64> for Point<N> {
+    type Output = Self;
+
+    fn mul(self, scale: i64) -> Self {
+        Point(self.0.map(|c| c * scale))
+    }
+}
+
+impl<const N: usize> Index<usize> for Point<N> {
+    type Output = i64;
+
+    fn index(&self, axis: usize) -> &i64 {
+        &self.0[axis]
+    }
+}
+
+impl<const N: usize> IndexMut<usize> for Point<N> {
+    fn index_mut(&mut self, axis: usize) -> &mut i64 {
+        &mut self.0[axis]
+    }
+}
+
+// Comma-separated coordinates, like "1, -6" or "1,2,3".
+impl<const N: usize> FromStr for Point<N> {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_line(s, |cursor| {
+            let mut point = Point::ORIGIN;
+            for axis in 0..N {
+                if axis > 0 {
+                    cursor.literal(",")?;
+                }
+                point.0[axis] = cursor.signed_integer()?;
+            }
+            Ok(point)
+        })
+    }
+}
+
+impl<const N: usize> fmt::Display for Point<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (axis, coordinate) in self.0.iter().enumerate() {
+            if axis > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", coordinate)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Turn {
+    Left,
+    Right,
+    Reverse,
+    Straight,
+}
+
+impl Direction {
+    // Clockwise from North.
+    pub const ALL: [Direction; 4] = [
+        Direction::North,
+        Direction::East,
+        Direction::South,
+        Direction::West,
+    ];
+
+    pub fn offset(&self) -> Point2 {
+        match self {
+            Direction::North => Point([0, -1]),
+            Direction::East => Point([1, 0]),
+            Direction::South => Point([0, 1]),
+            Direction::West => Point([-1, 0]),
+        }
+    }
+
+    pub fn turn(&self, turn: Turn) -> Direction {
+        let quarter_turns = match turn {
+            Turn::Straight => 0,
+            Turn::Right => 1,
+            Turn::Reverse => 2,
+            Turn::Left => 3,
+        };
+        Direction::ALL[(*self as usize + quarter_turns) % 4]
+    }
+
+    // Accepts compass points (N), screen directions (U) and arrows (^).
+    pub fn from_char(c: char) -> Option<Direction> {
+        match c {
+            'N' | 'U' | '^' => Some(Direction::North),
+            'E' | 'R' | '>' => Some(Direction::East),
+            'S' | 'D' | 'v' => Some(Direction::South),
+            'W' | 'L' | '<' => Some(Direction::West),
+            _ => None,
+        }
+    }
+}
+
+// The smallest box containing a set of points, including its edges.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct BoundingBox<const N: usize> {
+    pub min: Point<N>,
+    pub max: Point<N>,
+}
+
+impl<const N: usize> BoundingBox<N> {
+    // Returns `None` if there are no points.
+    pub fn from_points<'a>(points: impl IntoIterator<Item = &'a Point<N>>) -> Option<Self> {
+        let mut points = points.into_iter();
+        let first = *points.next()?;
+        Some(points.fold(
+            BoundingBox {
+                min: first,
+                max: first,
+            },
+            |mut bounds, point| {
+                for axis in 0..N {
+                    bounds.min[axis] = bounds.min[axis].min(point[axis]);
+                    bounds.max[axis] = bounds.max[axis].max(point[axis]);
+                }
+                bounds
+            },
+        ))
+    }
+
+    pub fn contains(&self, point: &Point<N>) -> bool {
+        (0..N).all(|axis| (self.min[axis]..=self.max[axis]).contains(&point[axis]))
+    }
+
+    // Whether `point` is in the box and touching one of its faces.
+    pub fn is_on_edge(&self, point: &Point<N>) -> bool {
+        self.contains(point)
+            && (0..N).any(|axis| point[axis] == self.min[axis] || point[axis] == self.max[axis])
+    }
+
+    // The number of lattice points along each axis.
+    pub fn size(&self) -> Point<N> {
+        self.max - self.min + Point([1; N])
+    }
+
+    pub fn volume(&self) -> i64 {
+        self.size().0.iter().product()
+    }
+
+    // Every lattice point in the box, with the first coordinate changing fastest.
+    pub fn points(&self) -> impl Iterator<Item = Point<N>> {
+        let (min, size) = (self.min, self.size());
+        (0..self.volume()).map(move |mut idx| {
+            let mut point = min;
+            for axis in 0..N {
+                point[axis] += idx % size[axis];
+                idx /= size[axis];
+            }
+            point
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BoundingBox, Direction, Point, Point2, Point3, Turn};
+    use std::collections::HashSet;
+
+    #[test]
+    fn check_arithmetic_and_distances() {
+        let a = Point([1, -2]);
+        let b = Point([-3, 4]);
+        assert_eq!(a + b, Point([-2, 2]));
+        assert_eq!(a - b, Point([4, -6]));
+        assert_eq!(-a * 2, Point([-2, 4]));
+        assert_eq!(a.manhattan_distance(&b), 10);
+        assert_eq!(a.chebyshev_distance(&b), 6);
+        assert_eq!(Point([1, -2, 3, -4]).manhattan(), 10);
+    }
+
+    #[test]
+    fn check_neighbours() {
+        assert_eq!(Point2::ORIGIN.neighbours().count(), 4);
+        assert_eq!(Point2::ORIGIN.all_neighbours().count(), 8);
+        assert_eq!(Point3::ORIGIN.all_neighbours().count(), 26);
+        assert!(Point([5, 5, 5, 5])
+            .all_neighbours()
+            .all(|p| p.chebyshev_distance(&Point([5, 5, 5, 5])) == 1));
+    }
+
+    #[test]
+    fn check_directions_and_rotations() {
+        assert_eq!(Direction::North.turn(Turn::Right), Direction::East);
+        assert_eq!(Direction::North.turn(Turn::Left), Direction::West);
+        assert_eq!(Direction::West.turn(Turn::Reverse), Direction::East);
+        for direction in Direction::ALL {
+            let right = direction.turn(Turn::Right);
+            assert_eq!(direction.offset().rotate_right(), right.offset());
+            assert_eq!(right.offset().rotate_left(), direction.offset());
+        }
+        assert_eq!(Direction::from_char('v'), Some(Direction::South));
+    }
+
+    #[test]
+    fn check_orientations() {
+        let orientations: HashSet<Point3> = Point([1, 2, 3]).orientations().into_iter().collect();
+        assert_eq!(orientations.len(), 24);
+        assert!(orientations.contains(&Point([1, 2, 3])));
+    }
+
+    #[test]
+    fn check_parse_and_display() {
+        assert_eq!("1, -6".parse::<Point2>(), Ok(Point([1, -6])));
+        assert_eq!("-1,2,3".parse::<Point3>(), Ok(Point([-1, 2, 3])));
+        assert!("1,2,3".parse::<Point2>().is_err());
+        assert!("1".parse::<Point2>().is_err());
+        assert_eq!(Point([3, -4]).to_string(), "3, -4");
+    }
+
+    #[test]
+    fn check_bounding_box() {
+        let points = [Point([-1, 2]), Point([3, -4]), Point([0, 0])];
+        let bounds = BoundingBox::from_points(&points).unwrap();
+        assert_eq!((bounds.min, bounds.max), (Point([-1, -4]), Point([3, 2])));
+        assert_eq!(bounds.volume(), 35);
+        assert_eq!(bounds.points().count(), 35);
+        assert!(bounds.points().all(|p| bounds.contains(&p)));
+        assert_eq!(bounds.points().filter(|p| bounds.is_on_edge(p)).count(), 20);
+        assert_eq!(BoundingBox::<2>::from_points(&[]), None);
+    }
+}
@@ -0,0 +1,123 @@
+// Scale testing: time a solver against generated inputs of increasing size.
+//
+// A day opts in by providing a generator that turns a size (e.g. a marble count or a number of
+// claims) into a valid puzzle input. Run it with `cargo run scale <DAY>` from a year's directory.
+use rand::rngs::StdRng;
+use std::fmt::Write;
+use std::time::{Duration, Instant};
+
+// Turn a size into a valid puzzle input, using only `rng` for randomness.
+pub type Generator = fn(usize, &mut StdRng) -> String;
+
+const BAR_WIDTH: f64 = 40.0;
+
+#[derive(Debug)]
+pub struct Sample {
+    pub size: usize,
+    pub input_bytes: usize,
+    pub elapsed: Duration,
+}
+
+// Generate an input of each size and time `solve` against it.
+pub fn measure<S>(generate: Generator, sizes: &[usize], rng: &mut StdRng, solve: S) -> Vec<Sample>
+where
+    S: Fn(&str),
+{
+    sizes
+        .iter()
+        .map(|&size| {
+            let input = generate(size, rng);
+            let start_time = Instant::now();
+            solve(&input);
+            Sample {
+                size,
+                input_bytes: input.len(),
+                elapsed: start_time.elapsed(),
+            }
+        })
+        .collect()
+}
+
+// Render the samples as a table with a bar chart of the runtimes.
+// The growth column estimates k, where the runtime is O(size^k), from the previous row.
+pub fn table(samples: &[Sample]) -> String {
+    let max_elapsed = samples
+        .iter()
+        .map(|sample| sample.elapsed.as_secs_f64())
+        .fold(f64::MIN_POSITIVE, f64::max);
+
+    let mut table = format!(
+        "{:>12} {:>12} {:>12} {:>8}  runtime\n",
+        "size", "bytes", "ms", "growth"
+    );
+    for (idx, sample) in samples.iter().enumerate() {
+        let elapsed = sample.elapsed.as_secs_f64();
+        let growth = match idx.checked_sub(1).map(|prev| &samples[prev]) {
+            Some(prev) if prev.size > 0 && sample.size > prev.size && !prev.elapsed.is_zero() => {
+                let exponent = (elapsed / prev.elapsed.as_secs_f64()).ln()
+                    / (sample.size as f64 / prev.size as f64).ln();
+                format!("{:.2}", exponent)
+            }
+            _ => "-".to_string(),
+        };
+        let bar = "#".repeat((elapsed / max_elapsed * BAR_WIDTH).round() as usize);
+        writeln!(
+            table,
+            "{:>12} {:>12} {:>12.3} {:>8}  {}",
+            sample.size,
+            sample.input_bytes,
+            elapsed * 1000.0,
+            growth,
+            bar
+        )
+        .unwrap();
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{measure, table, Sample};
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+    use std::cell::RefCell;
+    use std::time::Duration;
+
+    #[test]
+    fn check_measure_generates_each_size() {
+        let solved = RefCell::new(Vec::new());
+        let samples = measure(
+            |size, rng| (0..size).map(|_| rng.gen_range('a'..='z')).collect(),
+            &[1, 10, 100],
+            &mut StdRng::seed_from_u64(1),
+            |input| solved.borrow_mut().push(input.len()),
+        );
+        assert_eq!(solved.into_inner(), vec![1, 10, 100]);
+        assert_eq!(
+            samples.iter().map(|s| s.input_bytes).collect::<Vec<_>>(),
+            vec![1, 10, 100]
+        );
+    }
+
+    #[test]
+    fn check_table_estimates_growth() {
+        let samples = [
+            Sample {
+                size: 10,
+                input_bytes: 10,
+                elapsed: Duration::from_millis(1),
+            },
+            Sample {
+                size: 100,
+                input_bytes: 100,
+                elapsed: Duration::from_millis(100),
+            },
+        ];
+        let table = table(&samples);
+        let rows: Vec<&str> = table.lines().collect();
+        assert_eq!(rows.len(), 3);
+        assert!(rows[1].contains(" - "));
+        assert!(rows[2].contains("2.00"));
+        assert!(rows[2].ends_with(&"#".repeat(40)));
+    }
+}
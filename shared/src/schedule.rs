@@ -0,0 +1,207 @@
+// Scheduling the tasks of a dependency graph across a number of workers.
+//
+// A task can start once every task with an edge to it is finished. Whenever workers are idle,
+// the ready tasks are handed out smallest first, to the lowest-numbered idle workers, so the
+// schedule is unique. As in `graph`, the dependencies are given as a `successors` closure.
+use crate::graph::explore;
+use std::cmp::Reverse;
+use std::collections::{BTreeSet, BinaryHeap};
+use std::fmt::{Display, Write};
+
+// One task, done by worker number `worker` (counting from 0) from `start` until `end`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Task<N> {
+    pub node: N,
+    pub worker: usize,
+    pub start: u64,
+    pub end: u64,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Schedule<N> {
+    workers: usize,
+    // In order of when they start, then of worker.
+    tasks: Vec<Task<N>>,
+}
+
+// Schedule `nodes`, and every node reachable from them, across `workers` workers, where each
+// takes `duration` to do. Returns `None` if there's a cycle, as then some tasks can never start.
+pub fn schedule<N, S, I>(
+    nodes: impl IntoIterator<Item = N>,
+    successors: S,
+    workers: usize,
+    mut duration: impl FnMut(&N) -> u64,
+) -> Option<Schedule<N>>
+where
+    N: Clone + Ord,
+    S: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    assert!(workers > 0, "A schedule needs at least one worker.");
+    let (edges, mut in_degrees) = explore(nodes, successors);
+
+    let mut ready: BTreeSet<N> = in_degrees
+        .iter()
+        .filter(|&(_, &in_degree)| in_degree == 0)
+        .map(|(node, _)| node.clone())
+        .collect();
+    let mut idle: BTreeSet<usize> = (0..workers).collect();
+    // The index into `tasks` of each task in progress, by when it will be finished.
+    let mut in_progress: BinaryHeap<Reverse<(u64, usize)>> = BinaryHeap::new();
+    let mut tasks: Vec<Task<N>> = Vec::with_capacity(edges.len());
+    let mut time = 0;
+    loop {
+        while !idle.is_empty() && !ready.is_empty() {
+            let node = ready.pop_first().unwrap();
+            let worker = idle.pop_first().unwrap();
+            let end = time + duration(&node);
+            in_progress.push(Reverse((end, tasks.len())));
+            tasks.push(Task {
+                node,
+                worker,
+                start: time,
+                end,
+            });
+        }
+        let Some(&Reverse((finish, _))) = in_progress.peek() else {
+            break;
+        };
+        time = finish;
+        while let Some(&Reverse((finish, idx))) = in_progress.peek() {
+            if finish > time {
+                break;
+            }
+            in_progress.pop();
+            idle.insert(tasks[idx].worker);
+            for next in &edges[&tasks[idx].node] {
+                let in_degree = in_degrees.get_mut(next).unwrap();
+                *in_degree -= 1;
+                if *in_degree == 0 {
+                    ready.insert(next.clone());
+                }
+            }
+        }
+    }
+    (tasks.len() == edges.len()).then_some(Schedule { workers, tasks })
+}
+
+impl<N> Schedule<N> {
+    pub fn tasks(&self) -> &[Task<N>] {
+        &self.tasks
+    }
+
+    // When the last task is finished.
+    pub fn total_time(&self) -> u64 {
+        self.tasks.iter().map(|task| task.end).max().unwrap_or(0)
+    }
+
+    // The node that `worker` is working on at `time`, if any.
+    pub fn working_on(&self, worker: usize, time: u64) -> Option<&N> {
+        self.tasks
+            .iter()
+            .find(|task| task.worker == worker && task.start <= time && time < task.end)
+            .map(|task| &task.node)
+    }
+}
+
+impl<N: Display> Schedule<N> {
+    // A Gantt chart with a row per worker, at most `max_width` columns wide. Each column shows
+    // the first character of the node being worked on at the start of that column, or '.' if the
+    // worker is idle, and covers as many seconds as it takes to fit.
+    pub fn gantt(&self, max_width: usize) -> String {
+        let total_time = self.total_time();
+        let secs_per_column = total_time.div_ceil(max_width.max(1) as u64).max(1);
+        let mut chart = format!(
+            "{} workers, {} seconds, {} per column\n",
+            self.workers, total_time, secs_per_column
+        );
+        for worker in 0..self.workers {
+            let row: String = (0..total_time.div_ceil(secs_per_column))
+                .map(|column| {
+                    self.working_on(worker, column * secs_per_column)
+                        .and_then(|node| node.to_string().chars().next())
+                        .unwrap_or('.')
+                })
+                .collect();
+            writeln!(chart, "Worker {:<3}|{}|", worker + 1, row).unwrap();
+        }
+        chart
+    }
+
+    // One row per task, with the workers numbered from 1 as in the Gantt chart.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("worker,node,start,end\n");
+        for task in &self.tasks {
+            writeln!(
+                csv,
+                "{},{},{},{}",
+                task.worker + 1,
+                task.node,
+                task.start,
+                task.end
+            )
+            .unwrap();
+        }
+        csv
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::schedule;
+    use std::collections::HashMap;
+
+    // The example from 2018 day 7, with a step taking 1 second for A, 2 for B, and so on.
+    fn steps() -> HashMap<char, Vec<char>> {
+        HashMap::from([
+            ('C', vec!['A', 'F']),
+            ('A', vec!['B', 'D']),
+            ('B', vec!['E']),
+            ('D', vec!['E']),
+            ('F', vec!['E']),
+        ])
+    }
+
+    fn secs(step: &char) -> u64 {
+        *step as u64 - 'A' as u64 + 1
+    }
+
+    #[test]
+    fn check_schedule() {
+        let steps = steps();
+        let successors = |step: &char| steps.get(step).cloned().unwrap_or_default();
+        let plan = schedule(['C'], successors, 2, secs).unwrap();
+        assert_eq!(plan.total_time(), 15);
+        let order: String = plan.tasks().iter().map(|task| task.node).collect();
+        assert_eq!(order, "CAFBDE");
+        assert_eq!(
+            (plan.working_on(1, 4), plan.working_on(1, 9)),
+            (Some(&'F'), None)
+        );
+        assert_eq!(
+            plan.gantt(80),
+            "2 workers, 15 seconds, 1 per column\n\
+             Worker 1  |CCCABBDDDDEEEEE|\n\
+             Worker 2  |...FFFFFF......|\n"
+        );
+        assert_eq!(
+            plan.gantt(5),
+            "2 workers, 15 seconds, 3 per column\n\
+             Worker 1  |CADDE|\n\
+             Worker 2  |.FF..|\n"
+        );
+        assert!(plan
+            .to_csv()
+            .starts_with("worker,node,start,end\n1,C,0,3\n1,A,3,4\n2,F,3,9\n"));
+
+        // With one worker, the steps are done one at a time in topological order.
+        let plan = schedule(['C'], successors, 1, secs).unwrap();
+        assert_eq!(plan.total_time(), 21);
+    }
+
+    #[test]
+    fn check_schedule_of_cycle() {
+        let successors = |&n: &u32| [(n + 1) % 3];
+        assert_eq!(schedule([0], successors, 2, |_| 1), None);
+    }
+}
@@ -0,0 +1,95 @@
+// A summed-area table (a 2D prefix sum), for adding up any rectangle of a grid in O(1).
+//
+// `sums[(x, y)]` holds the total of every cell above and to the left of `(x, y)`, not including
+// that row and column, so the table is one bigger than the grid in each direction and the first
+// row and column are zero.
+use crate::grid::Grid;
+use std::ops::{Add, Sub};
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SummedAreaTable<T> {
+    sums: Grid<T>,
+}
+
+impl<T: Copy + Default + Add<Output = T> + Sub<Output = T>> SummedAreaTable<T> {
+    pub fn new(grid: &Grid<T>) -> Self {
+        Self::from_fn(grid.width(), grid.height(), |x, y| grid[(x, y)])
+    }
+
+    // Build the table for a `width` x `height` grid whose cells are given by `cell`, without
+    // making the grid first.
+    pub fn from_fn(width: usize, height: usize, mut cell: impl FnMut(usize, usize) -> T) -> Self {
+        let mut sums = Grid::new(width + 1, height + 1, T::default());
+        for y in 0..height {
+            for x in 0..width {
+                sums[(x + 1, y + 1)] =
+                    cell(x, y) + sums[(x, y + 1)] + sums[(x + 1, y)] - sums[(x, y)];
+            }
+        }
+        SummedAreaTable { sums }
+    }
+
+    // The width and height of the grid the table was built from.
+    pub fn width(&self) -> usize {
+        self.sums.width() - 1
+    }
+
+    pub fn height(&self) -> usize {
+        self.sums.height() - 1
+    }
+
+    // The total of the `width` x `height` region with its top left corner at `(x, y)`, as for
+    // `Grid::region`. The region must fit within the grid.
+    pub fn sum(&self, x: usize, y: usize, width: usize, height: usize) -> T {
+        assert!(x + width <= self.width() && y + height <= self.height());
+        let (right, bottom) = (x + width, y + height);
+        self.sums[(right, bottom)] + self.sums[(x, y)]
+            - self.sums[(x, bottom)]
+            - self.sums[(right, y)]
+    }
+
+    // The total of the whole grid.
+    pub fn total(&self) -> T {
+        self.sums[(self.width(), self.height())]
+    }
+}
+
+impl<T: Copy + Default + Add<Output = T> + Sub<Output = T>> From<&Grid<T>> for SummedAreaTable<T> {
+    fn from(grid: &Grid<T>) -> Self {
+        Self::new(grid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SummedAreaTable;
+    use crate::grid::Grid;
+
+    #[test]
+    fn check_sums_match_regions() {
+        let grid = Grid::from_fn(5, 4, |x, y| (x as i64 * 7 + y as i64 * 3) % 5 - 2);
+        let table = SummedAreaTable::new(&grid);
+        assert_eq!((table.width(), table.height()), (5, 4));
+        assert_eq!(
+            table.total(),
+            grid.iter().map(|(_, cell)| cell).sum::<i64>()
+        );
+        for (x, y) in grid.positions() {
+            for width in 0..=5 - x {
+                for height in 0..=4 - y {
+                    assert_eq!(
+                        table.sum(x, y, width, height),
+                        grid.region(x, y, width, height).sum::<i64>()
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn check_from_fn() {
+        let table = SummedAreaTable::from_fn(3, 3, |x, y| if x == y { 1.5 } else { 0.0 });
+        assert_eq!(table.total(), 4.5);
+        assert_eq!(table.sum(1, 0, 2, 2), 1.5);
+    }
+}
@@ -0,0 +1,248 @@
+// A disjoint-set forest over values of any hashable type, for grouping things into connected
+// components as links between them are discovered.
+//
+// Items are numbered in the order they're first seen, and components are listed in the order
+// of their earliest item, so results don't depend on hashing.
+use std::collections::HashMap;
+use std::hash::Hash;
+
+#[derive(Clone, Debug, Default)]
+pub struct UnionFind<T> {
+    index: HashMap<T, usize>,
+    items: Vec<T>,
+    parents: Vec<usize>,
+    ranks: Vec<u8>,
+    sizes: Vec<usize>,
+    component_count: usize,
+}
+
+// The state of the components after one `union` in `unions_over_time`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Snapshot {
+    // Whether the union joined two components that were separate.
+    pub merged: bool,
+    pub component_count: usize,
+    pub largest_size: usize,
+}
+
+impl<T: Clone + Eq + Hash> UnionFind<T> {
+    pub fn new() -> Self {
+        UnionFind {
+            index: HashMap::new(),
+            items: Vec::new(),
+            parents: Vec::new(),
+            ranks: Vec::new(),
+            sizes: Vec::new(),
+            component_count: 0,
+        }
+    }
+
+    // The number of items.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn component_count(&self) -> usize {
+        self.component_count
+    }
+
+    pub fn contains(&self, item: &T) -> bool {
+        self.index.contains_key(item)
+    }
+
+    // Add `item` in a component of its own, unless it's already there. Returns its number.
+    pub fn insert(&mut self, item: T) -> usize {
+        if let Some(&idx) = self.index.get(&item) {
+            return idx;
+        }
+        let idx = self.items.len();
+        self.index.insert(item.clone(), idx);
+        self.items.push(item);
+        self.parents.push(idx);
+        self.ranks.push(0);
+        self.sizes.push(1);
+        self.component_count += 1;
+        idx
+    }
+
+    // The root of the tree containing item number `idx`, halving the path to it on the way.
+    fn root(&mut self, mut idx: usize) -> usize {
+        while self.parents[idx] != idx {
+            self.parents[idx] = self.parents[self.parents[idx]];
+            idx = self.parents[idx];
+        }
+        idx
+    }
+
+    // The item that represents the component containing `item`.
+    pub fn find(&mut self, item: &T) -> Option<&T> {
+        let idx = *self.index.get(item)?;
+        let root = self.root(idx);
+        Some(&self.items[root])
+    }
+
+    // Put `a` and `b`, adding either if it's new, in the same component. Returns whether they
+    // were in different components before.
+    pub fn union(&mut self, a: T, b: T) -> bool {
+        let a = self.insert(a);
+        let b = self.insert(b);
+        let (a, b) = (self.root(a), self.root(b));
+        if a == b {
+            return false;
+        }
+        // Hang the shallower tree under the deeper one.
+        let (parent, child) = if self.ranks[a] < self.ranks[b] {
+            (b, a)
+        } else {
+            (a, b)
+        };
+        if self.ranks[parent] == self.ranks[child] {
+            self.ranks[parent] += 1;
+        }
+        self.parents[child] = parent;
+        self.sizes[parent] += self.sizes[child];
+        self.component_count -= 1;
+        true
+    }
+
+    pub fn connected(&mut self, a: &T, b: &T) -> bool {
+        match (self.index.get(a), self.index.get(b)) {
+            (Some(&a), Some(&b)) => self.root(a) == self.root(b),
+            _ => false,
+        }
+    }
+
+    // The number of items in the component containing `item`, or 0 if it's not been added.
+    pub fn component_size(&mut self, item: &T) -> usize {
+        match self.index.get(item) {
+            Some(&idx) => {
+                let root = self.root(idx);
+                self.sizes[root]
+            }
+            None => 0,
+        }
+    }
+
+    // The size of every component, largest first.
+    pub fn component_sizes(&mut self) -> Vec<usize> {
+        let mut sizes: Vec<usize> = (0..self.len())
+            .filter(|&idx| self.parents[idx] == idx)
+            .map(|root| self.sizes[root])
+            .collect();
+        sizes.sort_unstable_by(|a, b| b.cmp(a));
+        sizes
+    }
+
+    pub fn largest_component_size(&mut self) -> usize {
+        self.component_sizes().first().copied().unwrap_or(0)
+    }
+
+    // Every component, with its items in the order they were added.
+    pub fn components(&mut self) -> Vec<Vec<T>> {
+        let mut components: Vec<Vec<T>> = Vec::new();
+        let mut component_of_root = HashMap::new();
+        for idx in 0..self.len() {
+            let root = self.root(idx);
+            let component = *component_of_root.entry(root).or_insert_with(|| {
+                components.push(Vec::new());
+                components.len() - 1
+            });
+            components[component].push(self.items[idx].clone());
+        }
+        components
+    }
+
+    // Apply `links` one at a time, reporting on the components after each. This is lazy, so it
+    // can be stopped as soon as the components reach a state of interest.
+    pub fn unions_over_time<'a>(
+        &'a mut self,
+        links: impl IntoIterator<Item = (T, T)> + 'a,
+    ) -> impl Iterator<Item = Snapshot> + 'a {
+        // Components only ever grow, so the largest can be tracked from the ones that change.
+        let mut largest_size = self.largest_component_size();
+        links.into_iter().map(move |(a, b)| {
+            let merged = self.union(a.clone(), b);
+            largest_size = largest_size.max(self.component_size(&a));
+            Snapshot {
+                merged,
+                component_count: self.component_count,
+                largest_size,
+            }
+        })
+    }
+}
+
+impl<T: Clone + Eq + Hash> FromIterator<T> for UnionFind<T> {
+    // Each item starts in a component of its own.
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut union_find = UnionFind::new();
+        for item in iter {
+            union_find.insert(item);
+        }
+        union_find
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Snapshot, UnionFind};
+
+    #[test]
+    fn check_unions() {
+        let mut groups: UnionFind<&str> = ["a", "b", "c", "d", "e"].into_iter().collect();
+        assert_eq!(groups.component_count(), 5);
+        assert!(groups.union("a", "b"));
+        assert!(groups.union("d", "c"));
+        assert!(groups.union("b", "c"));
+        assert!(!groups.union("a", "d"));
+        assert!(groups.union("f", "g"));
+
+        assert_eq!((groups.len(), groups.component_count()), (7, 3));
+        assert!(groups.connected(&"a", &"c") && !groups.connected(&"a", &"e"));
+        assert!(!groups.connected(&"a", &"z"));
+        let root = groups.find(&"a").copied();
+        assert_eq!(groups.find(&"c").copied(), root);
+        assert_eq!(groups.find(&"z"), None);
+        assert_eq!(
+            (groups.component_size(&"d"), groups.component_size(&"z")),
+            (4, 0)
+        );
+        assert_eq!(groups.component_sizes(), vec![4, 2, 1]);
+        assert_eq!(
+            groups.components(),
+            vec![vec!["a", "b", "c", "d"], vec!["e"], vec!["f", "g"]]
+        );
+    }
+
+    #[test]
+    fn check_unions_over_time() {
+        // Points on a line join up when they're within 3 of each other.
+        let points = [0, 10, 2, 7, 3, 20];
+        let mut groups: UnionFind<i32> = points.into_iter().collect();
+        let mut links: Vec<(i32, i32)> = points
+            .iter()
+            .flat_map(|&a| points.iter().map(move |&b| (a, b)))
+            .filter(|&(a, b)| a < b && b - a <= 3)
+            .collect();
+        links.sort_by_key(|&(a, b)| b - a);
+
+        let snapshots: Vec<Snapshot> = groups.unions_over_time(links).collect();
+        assert_eq!(
+            snapshots
+                .iter()
+                .map(|s| s.component_count)
+                .collect::<Vec<_>>(),
+            vec![5, 4, 4, 3]
+        );
+        assert_eq!(snapshots.last().unwrap().largest_size, 3);
+        assert!(!snapshots[2].merged);
+        assert_eq!(
+            groups.components(),
+            vec![vec![0, 2, 3], vec![10, 7], vec![20]]
+        );
+    }
+}
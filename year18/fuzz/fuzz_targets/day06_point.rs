@@ -0,0 +1,8 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use shared::point::Point2;
+
+fuzz_target!(|input: &str| {
+    let _ = input.parse::<Point2>();
+});
@@ -0,0 +1,13 @@
+#![no_main]
+#![allow(dead_code)]
+
+use libfuzzer_sys::fuzz_target;
+
+#[path = "../../src/day10.rs"]
+mod day10;
+#[path = "../../src/visualize.rs"]
+mod visualize;
+
+fuzz_target!(|input: &str| {
+    let _ = input.parse::<day10::Star>();
+});
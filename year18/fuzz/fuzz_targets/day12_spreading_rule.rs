@@ -0,0 +1,13 @@
+#![no_main]
+#![allow(dead_code)]
+
+use libfuzzer_sys::fuzz_target;
+
+#[path = "../../src/visualize.rs"]
+mod visualize;
+#[path = "../../src/day12.rs"]
+mod day12;
+
+fuzz_target!(|input: &str| {
+    let _ = input.parse::<day12::SpreadingRule>();
+});
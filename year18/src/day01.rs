@@ -1,25 +1,39 @@
-use std::collections::HashSet;
+use rand::rngs::StdRng;
+use rand::Rng;
+use shared::cycle::first_repeat;
+use std::iter;
 
 // Potential improvements:
 //
 pub fn day01(input_lines: &[Vec<String>]) -> (String, String) {
-    let answer1 = input_lines[0].iter().fold(0, |accumulator, line| {
-        accumulator + line.parse::<i32>().unwrap()
-    });
-    let mut frequencies = HashSet::new();
-    let mut accumulator = 0;
-    frequencies.insert(accumulator);
-    for line in input_lines[0].iter().cycle() {
-        accumulator += line.parse::<i32>().unwrap();
-        if !frequencies.insert(accumulator) {
-            break;
-        }
-    }
-    let answer2 = accumulator;
+    let changes: Vec<i32> = input_lines[0]
+        .iter()
+        .map(|line| line.parse::<i32>().unwrap())
+        .collect();
+    let answer1: i32 = changes.iter().sum();
+
+    // The frequency after each change, going round the list forever, starting from 0.
+    let frequencies = iter::once(0).chain(changes.iter().cycle().scan(0, |frequency, change| {
+        *frequency += change;
+        Some(*frequency)
+    }));
+    let (_, _, answer2) = first_repeat(frequencies, |&frequency| frequency).unwrap();
 
     (format!("{}", answer1), format!("{}", answer2))
 }
 
+// Generate `size` frequency changes for `cargo run scale 1`.
+// The changes sum to zero, so that the frequency is guaranteed to repeat.
+pub fn generate(size: usize, rng: &mut StdRng) -> String {
+    let mut changes: Vec<i32> = (1..size).map(|_| rng.gen_range(-100..=100)).collect();
+    changes.push(-changes.iter().sum::<i32>());
+    changes
+        .iter()
+        .map(|change| format!("{:+}", change))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
 #[cfg(test)]
 mod tests {
     use super::day01;
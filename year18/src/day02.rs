@@ -1,5 +1,7 @@
 // Potential improvements:
 //
+use rand::rngs::StdRng;
+use rand::Rng;
 use std::collections::{HashMap, HashSet};
 
 pub fn day02(input_lines: &[Vec<String>]) -> (String, String) {
@@ -59,6 +61,23 @@ pub fn day02(input_lines: &[Vec<String>]) -> (String, String) {
     (answer1.to_string(), answer2)
 }
 
+// Generate `size` box IDs for `cargo run scale 2`.
+// Exactly one pair of IDs differs by a single character.
+pub fn generate(size: usize, rng: &mut StdRng) -> String {
+    const ID_LEN: usize = 26;
+    let mut ids: Vec<Vec<u8>> = (1..size.max(2))
+        .map(|_| (0..ID_LEN).map(|_| rng.gen_range(b'a'..=b'z')).collect())
+        .collect();
+    let mut near_duplicate = ids[0].clone();
+    let idx = rng.gen_range(0..ID_LEN);
+    near_duplicate[idx] = b'a' + (near_duplicate[idx] - b'a' + 1) % 26;
+    ids.insert(rng.gen_range(1..=ids.len()), near_duplicate);
+    ids.into_iter()
+        .map(|id| String::from_utf8(id).unwrap())
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
 #[cfg(test)]
 mod tests {
     use super::day02;
@@ -1,89 +1,77 @@
 use itertools::Itertools;
-use regex::Regex;
-use std::collections::HashSet;
+use rand::rngs::StdRng;
+use rand::Rng;
+use shared::interval::Cuboid;
+use shared::parse_error::{parse_lines, ParseError};
+use shared::parser::parse_line;
+use shared::point::{Point, Point2};
+use std::collections::{BTreeSet, HashSet};
 use std::hash::Hash;
 use std::str::FromStr;
-use std::time::Instant;
-
-#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
-struct SquareInch {
-    x: i32,
-    y: i32,
-}
 
 #[derive(Debug, Eq, Hash, PartialEq)]
 struct FabricClaim {
     id: i32,
-    top_left: SquareInch,
-    width: i32,
-    height: i32,
+    area: Cuboid<2>,
 }
 
 impl FabricClaim {
-    fn new(id: i32, x: i32, y: i32, width: i32, height: i32) -> Self {
+    fn new(id: i32, x: i64, y: i64, width: i64, height: i64) -> Self {
         Self {
             id,
-            top_left: SquareInch { x, y },
-            width,
-            height,
+            area: Cuboid::from_corner_and_size(Point([x, y]), Point([width, height])),
         }
     }
 
-    fn right_edge_x(&self) -> i32 {
-        self.top_left.x + self.width - 1
+    fn all_sq_inches(&self) -> impl Iterator<Item = Point2> {
+        self.area.points()
     }
 
-    fn bottom_edge_y(&self) -> i32 {
-        self.top_left.y + self.height - 1
+    // The square inches claimed by both `self` and `other`.
+    fn intersection(&self, other: &FabricClaim) -> impl Iterator<Item = Point2> {
+        self.area
+            .intersection(&other.area)
+            .into_iter()
+            .flat_map(|common| common.points())
     }
 
-    fn all_sq_inches(&self) -> Vec<SquareInch> {
-        let mut sq_inches = Vec::with_capacity(self.height as usize * self.width as usize);
-        for x in self.top_left.x..self.right_edge_x() + 1 {
-            for y in self.top_left.y..self.bottom_edge_y() + 1 {
-                sq_inches.push(SquareInch { x, y });
-            }
-        }
-        sq_inches
+    fn overlaps(&self, other: &FabricClaim) -> bool {
+        self.area.overlaps(&other.area)
     }
+}
 
-    fn from_input_line(input_line: &str) -> FabricClaim {
-        let re = Regex::new(r"#(\d+) @ (\d+),(\d+): (\d+)x(\d+)").unwrap();
-        re.captures(input_line)
-            .map(|cap| {
-                let id = FromStr::from_str(&cap[1]).unwrap();
-                let x = FromStr::from_str(&cap[2]).unwrap();
-                let y = FromStr::from_str(&cap[3]).unwrap();
-                let width = FromStr::from_str(&cap[4]).unwrap();
-                let height = FromStr::from_str(&cap[5]).unwrap();
-                FabricClaim::new(id, x, y, width, height)
-            })
-            .expect("Failed to match regular expression against input.")
+impl FromStr for FabricClaim {
+    type Err = ParseError;
+
+    // A claim looks like "#123 @ 3,2: 5x4".
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_line(s, |cursor| {
+            cursor.literal("#")?;
+            let id = cursor.integer()?;
+            cursor.literal(" @ ")?;
+            let x = cursor.integer()?;
+            cursor.literal(",")?;
+            let y = cursor.integer()?;
+            cursor.literal(": ")?;
+            let width = cursor.integer()?;
+            cursor.literal("x")?;
+            let height = cursor.integer()?;
+            Ok(FabricClaim::new(id, x, y, width, height))
+        })
     }
+}
 
-    fn overlaps(&self, other: &FabricClaim) -> bool {
-        !self.top_left.x > other.right_edge_x()
-            || self.right_edge_x() < other.top_left.x
-            || self.top_left.y > other.bottom_edge_y()
-            || self.bottom_edge_y() < other.top_left.y
-    }
+fn parse_claims(input_lines: &[Vec<String>]) -> Vec<FabricClaim> {
+    parse_lines(input_lines, 0).unwrap_or_else(|error| panic!("{}", error))
 }
 
+// Count how many claims cover each square inch, then find the claims whose square inches are
+// covered only once.
 pub fn day03(input_lines: &[Vec<String>]) -> (String, String) {
-    let now = Instant::now();
-
-    let fabric_claims: Vec<FabricClaim> = input_lines[0]
-        .iter()
-        .map(|line| FabricClaim::from_input_line(line))
-        .collect();
+    let fabric_claims = parse_claims(input_lines);
 
-    println!(
-        "Finished parsing input lines after {}ms.",
-        now.elapsed().as_millis()
-    );
-
-    let mut claimed_sq_inches = HashSet::<SquareInch>::new();
-    let mut contested_sq_inches = HashSet::<SquareInch>::new();
+    let mut claimed_sq_inches = HashSet::<Point2>::new();
+    let mut contested_sq_inches = HashSet::<Point2>::new();
     for claim in &fabric_claims {
         for sq_inch in claim.all_sq_inches() {
             if !claimed_sq_inches.insert(sq_inch) {
@@ -92,12 +80,33 @@ pub fn day03(input_lines: &[Vec<String>]) -> (String, String) {
         }
     }
 
-    let mut uncontested_claim_ids: HashSet<i32> =
-        HashSet::from_iter(1..fabric_claims.len() as i32 + 1);
+    let uncontested_claim_ids: BTreeSet<i32> = fabric_claims
+        .iter()
+        .filter(|claim| {
+            claim
+                .all_sq_inches()
+                .all(|sq_inch| !contested_sq_inches.contains(&sq_inch))
+        })
+        .map(|claim| claim.id)
+        .collect();
+
+    let answer1 = contested_sq_inches.len();
+    let answer2 = uncontested_claim_ids;
+    (format!("{}", answer1), format!("{:?}", answer2))
+}
+
+// Compare every pair of claims, collecting the square inches where they overlap.
+pub fn day03_pairwise(input_lines: &[Vec<String>]) -> (String, String) {
+    let fabric_claims = parse_claims(input_lines);
+
+    let mut contested_sq_inches = HashSet::<Point2>::new();
+    let mut uncontested_claim_ids: BTreeSet<i32> =
+        fabric_claims.iter().map(|claim| claim.id).collect();
     for combination in fabric_claims.iter().combinations(2) {
         let this_claim = combination[0];
         let that_claim = combination[1];
         if this_claim.overlaps(that_claim) {
+            contested_sq_inches.extend(this_claim.intersection(that_claim));
             uncontested_claim_ids.remove(&this_claim.id);
             uncontested_claim_ids.remove(&that_claim.id);
         }
@@ -108,10 +117,32 @@ pub fn day03(input_lines: &[Vec<String>]) -> (String, String) {
     (format!("{}", answer1), format!("{:?}", answer2))
 }
 
+// Generate `size` fabric claims on a 1000 x 1000 sheet for `cargo run scale 3`.
+pub fn generate(size: usize, rng: &mut StdRng) -> String {
+    (1..=size)
+        .map(|id| {
+            let width = rng.gen_range(1..=30);
+            let height = rng.gen_range(1..=30);
+            format!(
+                "#{} @ {},{}: {}x{}",
+                id,
+                rng.gen_range(0..=1000 - width),
+                rng.gen_range(0..=1000 - height),
+                width,
+                height
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
 #[cfg(test)]
 mod tests {
-    use super::day03;
-    use crate::utils::load_input;
+    use super::{day03, day03_pairwise, generate, FabricClaim};
+    use crate::utils::{load_input, DEFAULT_SEED};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use shared::fuzz::assert_parser_never_panics;
 
     #[test]
     fn check_day03_case01() {
@@ -122,6 +153,19 @@ mod tests {
         )
     }
 
+    #[test]
+    fn check_day03_pairwise_agrees() {
+        let input_lines = load_input("#1 @ 1,3: 4x4\n#2 @ 3,1: 4x4\n#3 @ 5,5: 2x2\n#4 @ 6,1: 1x2");
+        assert_eq!(day03_pairwise(&input_lines), day03(&input_lines));
+        let input_lines = load_input(&generate(200, &mut StdRng::seed_from_u64(DEFAULT_SEED)));
+        assert_eq!(day03_pairwise(&input_lines), day03(&input_lines));
+    }
+
+    #[test]
+    fn check_day03_parser_never_panics() {
+        assert_parser_never_panics::<FabricClaim>(&["#1 @ 1,3: 4x4", "#123 @ 3,2: 5x4"]);
+    }
+
     fn full_test(input_text: &str, part1_result: &str, part2_result: &str) {
         let input_lines = load_input(input_text);
         assert_eq!(
@@ -1,13 +1,94 @@
-use chrono::prelude::*;
 use counter::Counter;
-use regex::Regex;
-use std::cmp::Ordering;
+use itertools::Itertools;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use shared::parse_error::{parse_lines, ParseError};
+use shared::parser::{parse_line, Cursor};
+use std::cmp::Reverse;
 use std::collections::HashMap;
+use std::error::Error;
 use std::fmt;
-use std::iter::FromIterator;
+use std::ops::{Range, RangeInclusive};
 use std::str::FromStr;
 
-#[derive(Debug, PartialEq, Eq, PartialOrd)]
+// A time such as "1518-11-01 23:58". The fields are in order of significance, so the derived
+// ordering is chronological. Any year is allowed, including those before 1970.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+struct Timestamp {
+    year: u32,
+    month: u32,
+    day: u32,
+    hour: u32,
+    minute: u32,
+}
+
+fn is_leap_year(year: u32) -> bool {
+    year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400))
+}
+
+fn days_in_month(year: u32, month: u32) -> u32 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+// Parse a number that has to be in `range`, pointing at it if it isn't.
+fn bounded_integer(
+    cursor: &mut Cursor,
+    range: RangeInclusive<u32>,
+    expected: &str,
+) -> Result<u32, ParseError> {
+    let start = *cursor;
+    let value = cursor.integer()?;
+    if range.contains(&value) {
+        Ok(value)
+    } else {
+        Err(start.error(start.rest().len() - cursor.rest().len(), expected))
+    }
+}
+
+impl Timestamp {
+    // Parse "YYYY-MM-DD HH:MM".
+    fn parse(cursor: &mut Cursor) -> Result<Self, ParseError> {
+        let year = cursor.integer()?;
+        cursor.literal("-")?;
+        let month = bounded_integer(cursor, 1..=12, "a month from 1 to 12")?;
+        cursor.literal("-")?;
+        let last_day = days_in_month(year, month);
+        let day = bounded_integer(
+            cursor,
+            1..=last_day,
+            &format!("a day from 1 to {}", last_day),
+        )?;
+        cursor.literal(" ")?;
+        let hour = bounded_integer(cursor, 0..=23, "an hour from 0 to 23")?;
+        cursor.literal(":")?;
+        let minute = bounded_integer(cursor, 0..=59, "a minute from 0 to 59")?;
+        Ok(Timestamp {
+            year,
+            month,
+            day,
+            hour,
+            minute,
+        })
+    }
+}
+
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:04}-{:02}-{:02} {:02}:{:02}",
+            self.year, self.month, self.day, self.hour, self.minute
+        )
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
 enum SecurityEventType {
     Wake,
     Sleep,
@@ -25,135 +106,332 @@ impl fmt::Display for SecurityEventType {
     }
 }
 
-#[derive(Debug, Eq, PartialEq, PartialOrd)]
+#[derive(Debug, Eq, PartialEq)]
 struct SecurityEvent {
-    time: DateTime<Utc>,
-    minute: u32,
+    time: Timestamp,
     event_type: SecurityEventType,
 }
 
-impl SecurityEvent {
-    fn new(time: DateTime<Utc>, event_type: SecurityEventType) -> Self {
-        let minute = time.minute();
-        Self {
-            time,
-            minute,
-            event_type,
-        }
+impl FromStr for SecurityEvent {
+    type Err = ParseError;
+
+    // An event looks like "[1518-11-01 23:58] Guard #99 begins shift", "[1518-11-02 00:40] falls
+    // asleep" or "[1518-11-02 00:50] wakes up".
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_line(s, |cursor| {
+            cursor.literal("[")?;
+            let time = Timestamp::parse(cursor)?;
+            cursor.literal("] ")?;
+            let event_type = if cursor.literal("wakes up").is_ok() {
+                SecurityEventType::Wake
+            } else if cursor.literal("falls asleep").is_ok() {
+                SecurityEventType::Sleep
+            } else if cursor.literal("Guard #").is_ok() {
+                let id = cursor.integer()?;
+                cursor.literal(" begins shift")?;
+                SecurityEventType::StartShift(id)
+            } else {
+                return Err(cursor.error(
+                    usize::MAX,
+                    "\"wakes up\", \"falls asleep\" or \"Guard #<id> begins shift\"",
+                ));
+            };
+            Ok(SecurityEvent { time, event_type })
+        })
     }
+}
+
+// A way in which the events, once sorted, don't make sense as a log of guard shifts.
+#[derive(Debug, Eq, PartialEq)]
+enum LogError {
+    DuplicateTime(Timestamp),
+    NoGuardOnDuty(Timestamp),
+    // Guards only sleep during the midnight hour, so every minute asleep is in 00:00 to 00:59.
+    OutsideMidnightHour(Timestamp),
+    AlreadyAsleep { guard_id: u32, time: Timestamp },
+    AlreadyAwake { guard_id: u32, time: Timestamp },
+    // The guard woke up on a later day than they fell asleep, so the nap left the midnight hour.
+    WokeOnAnotherDay { guard_id: u32, time: Timestamp },
+    // The guard was still asleep when the next shift started or the log ended.
+    AsleepAtEndOfShift { guard_id: u32, time: Timestamp },
+}
 
-    fn from_input_line(input_line: &str) -> Self {
-        // We can't parse dates before 1970 into a DateTime object.
-        // The actual year doesn't matter, only the order and the minute matter.
-        // So manipulate the strings to effectively add 1000 years to all each date.
-        let mut input_line_chars: Vec<char> = input_line.clone().chars().collect();
-        input_line_chars[1] = '2';
-        let input_line_plus_1000y = String::from_iter(input_line_chars);
-
-        // Split the line on either "[" or "] ".
-        // E.g., "[1518-10-09 00:56] wakes up" goes to ['', '2518-10-09 00:56', 'wakes up'].
-        let re = Regex::new(r"\[|\] ").unwrap();
-        let split_line: Vec<&str> = re.split(&input_line_plus_1000y).collect();
-        let date_str = split_line[1];
-        let event_str = split_line[2];
-
-        // Parse the str type date into a DateTime object.
-        let time = dateparser::parse(date_str).unwrap();
-
-        // Parse the str type event into a SecurityEventType object.
-        let event_type = match event_str {
-            "wakes up" => SecurityEventType::Wake,
-            "falls asleep" => SecurityEventType::Sleep,
-            description => {
-                let re = Regex::new(r"Guard #(\d+) begins shift").unwrap();
-                re.captures(description)
-                    .map(|cap| {
-                        let id = FromStr::from_str(&cap[1]).unwrap();
-                        SecurityEventType::StartShift(id)
-                    })
-                    .expect("Failed to capture regular expression from security event.")
+impl fmt::Display for LogError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DuplicateTime(time) => write!(f, "There are two events at {}.", time),
+            Self::NoGuardOnDuty(time) => {
+                write!(f, "No guard is on duty to sleep or wake at {}.", time)
             }
-        };
-        SecurityEvent::new(time, event_type)
+            Self::OutsideMidnightHour(time) => write!(
+                f,
+                "Guards only sleep or wake during the midnight hour, not at {}.",
+                time
+            ),
+            Self::AlreadyAsleep { guard_id, time } => write!(
+                f,
+                "Guard {} falls asleep at {} but is already asleep.",
+                guard_id, time
+            ),
+            Self::AlreadyAwake { guard_id, time } => write!(
+                f,
+                "Guard {} wakes up at {} but is already awake.",
+                guard_id, time
+            ),
+            Self::WokeOnAnotherDay { guard_id, time } => write!(
+                f,
+                "Guard {} wakes up at {} but fell asleep on an earlier day.",
+                guard_id, time
+            ),
+            Self::AsleepAtEndOfShift { guard_id, time } => write!(
+                f,
+                "Guard {} is still asleep at the end of their shift, after falling asleep at {}.",
+                guard_id, time
+            ),
+        }
     }
 }
 
-impl Ord for SecurityEvent {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        if self.time < other.time {
-            return Ordering::Less;
-        }
-        if self.time > other.time {
-            return Ordering::Greater;
+impl Error for LogError {}
+
+// Sort the events and work out when each guard was asleep. The key is the ID of a guard and the
+// value is the minutes past midnight of each of their naps, in chronological order. Every guard
+// who started a shift is included, even if they never slept.
+fn naps_per_guard(
+    mut events: Vec<SecurityEvent>,
+) -> Result<HashMap<u32, Vec<Range<u32>>>, LogError> {
+    events.sort_by_key(|event| event.time);
+    if let Some(pair) = events.windows(2).find(|pair| pair[0].time == pair[1].time) {
+        return Err(LogError::DuplicateTime(pair[0].time));
+    }
+
+    let mut naps = HashMap::<u32, Vec<Range<u32>>>::new();
+    // The guard on duty, and when they fell asleep if they're asleep.
+    let mut on_duty: Option<(u32, Option<Timestamp>)> = None;
+    for event in events {
+        match (event.event_type, &mut on_duty) {
+            (SecurityEventType::StartShift(id), _) => {
+                if let Some((guard_id, Some(time))) = on_duty {
+                    return Err(LogError::AsleepAtEndOfShift { guard_id, time });
+                }
+                naps.entry(id).or_default();
+                on_duty = Some((id, None));
+            }
+            (_, None) => return Err(LogError::NoGuardOnDuty(event.time)),
+            (_, Some(_)) if event.time.hour != 0 => {
+                return Err(LogError::OutsideMidnightHour(event.time))
+            }
+            (SecurityEventType::Sleep, Some((guard_id, asleep_since))) => {
+                if asleep_since.is_some() {
+                    return Err(LogError::AlreadyAsleep {
+                        guard_id: *guard_id,
+                        time: event.time,
+                    });
+                }
+                *asleep_since = Some(event.time);
+            }
+            (SecurityEventType::Wake, Some((guard_id, asleep_since))) => {
+                let fell_asleep = asleep_since.take().ok_or(LogError::AlreadyAwake {
+                    guard_id: *guard_id,
+                    time: event.time,
+                })?;
+                // A nap must end later in the midnight hour it started in. The events are sorted,
+                // so a wake at an earlier or equal minute can only be on another day.
+                let same_day = (fell_asleep.year, fell_asleep.month, fell_asleep.day)
+                    == (event.time.year, event.time.month, event.time.day);
+                if !same_day || event.time.minute <= fell_asleep.minute {
+                    return Err(LogError::WokeOnAnotherDay {
+                        guard_id: *guard_id,
+                        time: event.time,
+                    });
+                }
+                naps.entry(*guard_id)
+                    .or_default()
+                    .push(fell_asleep.minute..event.time.minute);
+            }
         }
-        Ordering::Equal
     }
+    if let Some((guard_id, Some(time))) = on_duty {
+        return Err(LogError::AsleepAtEndOfShift { guard_id, time });
+    }
+    Ok(naps)
 }
 
-fn guard_records_valid(events: &[SecurityEvent]) -> bool {
-    events.chunks(2).all(|pair| {
-        pair[0].event_type == SecurityEventType::Sleep
-            && pair[1].event_type == SecurityEventType::Wake
-    })
+fn count_minutes_asleep(naps: &[Range<u32>]) -> u32 {
+    naps.iter().map(|nap| nap.end - nap.start).sum()
 }
 
-fn count_minutes_asleep(events: &[SecurityEvent]) -> u32 {
-    events.chunks(2).fold(0, |mut total, pair| {
-        total += pair[1].minute - pair[0].minute;
-        total
-    })
+fn map_minutes_asleep(naps: &[Range<u32>]) -> Counter<u32> {
+    naps.iter().cloned().flatten().collect()
 }
 
-fn map_minutes_asleep(events: &[SecurityEvent]) -> Counter<u32> {
-    events.chunks(2).fold(Counter::new(), |mut counter, pair| {
-        counter.extend((pair[0].minute..pair[1].minute).collect::<Counter<u32>>());
-        counter
-    })
+// Guards only sleep during the midnight hour.
+const MINUTES: usize = 60;
+
+// The characters of the heatmap, from never asleep to asleep on the most shifts.
+const SHADES: &[u8] = b" .:-=+*#%@";
+
+// How many entries the report lists for each query.
+const REPORT_TOP_N: usize = 5;
+
+// For each guard, how many of their shifts they were asleep for on each minute past midnight.
+// The rows are in order of guard ID.
+#[derive(Debug)]
+struct SleepMatrix {
+    rows: Vec<(u32, [u32; MINUTES])>,
 }
 
-pub fn day04(input_lines: &[Vec<String>]) -> (String, String) {
-    // Parse the input into a vector of all security events and sort these chronologically.
-    let mut all_security_events: Vec<SecurityEvent> = input_lines[0]
-        .iter()
-        .map(|line| SecurityEvent::from_input_line(line))
-        .collect();
-    all_security_events.sort();
-
-    // Convert the vector of all security events into a HashMap where the key is the ID of a
-    // guard and the value is a vector of all the Wake and Sleep security events associated
-    // with that guard.
-    let mut guard_id: u32 = 0;
-    let mut security_events_per_guard = HashMap::<u32, Vec<SecurityEvent>>::new();
-    for security_event in all_security_events {
-        if let SecurityEventType::StartShift(id) = security_event.event_type {
-            guard_id = id;
-            security_events_per_guard
-                .entry(guard_id)
-                .or_insert_with(Vec::new);
-        } else {
-            security_events_per_guard
-                .entry(guard_id)
-                .and_modify(|events| events.push(security_event));
+impl SleepMatrix {
+    fn new(sleep_schedules_per_guard: &HashMap<u32, Counter<u32>>) -> Self {
+        let mut rows: Vec<(u32, [u32; MINUTES])> = sleep_schedules_per_guard
+            .iter()
+            .map(|(&guard_id, sleep_schedule)| {
+                let mut row = [0; MINUTES];
+                for (&minute, &count) in sleep_schedule.iter() {
+                    row[minute as usize] = count as u32;
+                }
+                (guard_id, row)
+            })
+            .collect();
+        rows.sort_unstable_by_key(|&(guard_id, _)| guard_id);
+        SleepMatrix { rows }
+    }
+
+    // The `n` guards who spent the most minutes asleep, with those totals, most first.
+    fn sleepiest_guards(&self, n: usize) -> Vec<(u32, u32)> {
+        let mut totals: Vec<(u32, u32)> = self
+            .rows
+            .iter()
+            .map(|(guard_id, row)| (*guard_id, row.iter().sum()))
+            .collect();
+        totals.sort_by_key(|&(guard_id, total)| (Reverse(total), guard_id));
+        totals.truncate(n);
+        totals
+    }
+
+    // The `n` guards who were asleep on the same minute on the most shifts, with that minute and
+    // number of shifts, most first. Each guard is listed once, with the earliest such minute.
+    fn most_consistent_sleepers(&self, n: usize) -> Vec<(u32, u32, u32)> {
+        let mut sleepers: Vec<(u32, u32, u32)> = self
+            .rows
+            .iter()
+            .filter_map(|(guard_id, row)| {
+                let count = *row.iter().max()?;
+                let minute = row.iter().position(|&c| c == count)? as u32;
+                (count > 0).then_some((*guard_id, minute, count))
+            })
+            .collect();
+        sleepers.sort_by_key(|&(guard_id, _, count)| (Reverse(count), guard_id));
+        sleepers.truncate(n);
+        sleepers
+    }
+
+    // The minute on which the most guards have slept, with the number of guards. Ties go to the
+    // minute slept through on the most shifts, and then to the earliest.
+    fn most_contested_minute(&self) -> Option<(u32, usize)> {
+        (0..MINUTES)
+            .map(|minute| {
+                let guards = self.rows.iter().filter(|(_, row)| row[minute] > 0).count();
+                let shifts: u32 = self.rows.iter().map(|(_, row)| row[minute]).sum();
+                (minute as u32, guards, shifts)
+            })
+            .filter(|&(_, guards, _)| guards > 0)
+            .max_by_key(|&(minute, guards, shifts)| (guards, shifts, Reverse(minute)))
+            .map(|(minute, guards, _)| (minute, guards))
+    }
+
+    // One row per guard, shaded by how often they were asleep on each minute relative to the
+    // most often any guard was asleep on any minute, and followed by their total minutes asleep.
+    fn heatmap(&self) -> String {
+        let max_count = self
+            .rows
+            .iter()
+            .flat_map(|(_, row)| row.iter().copied())
+            .max()
+            .unwrap_or(0)
+            .max(1);
+        let tens: String = (0..MINUTES)
+            .map(|minute| (b'0' + minute as u8 / 10) as char)
+            .collect();
+        let units: String = (0..MINUTES)
+            .map(|minute| (b'0' + minute as u8 % 10) as char)
+            .collect();
+        let mut heatmap = format!("  guard  {}\n         {}\n", tens, units);
+        for (guard_id, row) in &self.rows {
+            // Round up, so that a single shift asleep still shows.
+            let cells: String = row
+                .iter()
+                .map(|&count| {
+                    let shade = (count as usize * (SHADES.len() - 1)).div_ceil(max_count as usize);
+                    SHADES[shade] as char
+                })
+                .collect();
+            let total: u32 = row.iter().sum();
+            heatmap += &format!("{:>7} |{}| {}\n", guard_id, cells, total);
         }
+        heatmap
     }
-    for (guard_id, events) in &security_events_per_guard {
-        if !guard_records_valid(events) {
-            panic!(
-                "The records for security guard {} must alternate between Sleep and Wake.",
-                guard_id
-            );
+
+    // A header row of "guard" and the minutes, then a row of counts for each guard.
+    fn to_csv(&self) -> String {
+        let mut csv = format!("guard,{}\n", (0..MINUTES).join(","));
+        for (guard_id, row) in &self.rows {
+            csv += &format!("{},{}\n", guard_id, row.iter().join(","));
         }
+        csv
     }
+}
+
+fn parse_naps(input_lines: &[Vec<String>]) -> HashMap<u32, Vec<Range<u32>>> {
+    let security_events: Vec<SecurityEvent> =
+        parse_lines(input_lines, 0).unwrap_or_else(|error| panic!("{}", error));
+    naps_per_guard(security_events).unwrap_or_else(|error| panic!("{}", error))
+}
+
+// The sleep matrix as a heatmap followed by answers to some general queries, and as CSV, for
+// `cargo run report 4`.
+pub fn report(input_lines: &[Vec<String>]) -> (String, String) {
+    let sleep_schedules_per_guard: HashMap<u32, Counter<u32>> = parse_naps(input_lines)
+        .iter()
+        .map(|(&guard_id, naps)| (guard_id, map_minutes_asleep(naps)))
+        .collect();
+    let matrix = SleepMatrix::new(&sleep_schedules_per_guard);
+
+    let mut text = format!(
+        "Minutes past midnight asleep (darker is more shifts):\n{}\nSleepiest guards:\n",
+        matrix.heatmap()
+    );
+    for (guard_id, total) in matrix.sleepiest_guards(REPORT_TOP_N) {
+        text += &format!("  #{}: {} minutes\n", guard_id, total);
+    }
+    text += "Most consistent sleepers:\n";
+    for (guard_id, minute, count) in matrix.most_consistent_sleepers(REPORT_TOP_N) {
+        text += &format!(
+            "  #{}: asleep at 00:{:02} on {} shifts\n",
+            guard_id, minute, count
+        );
+    }
+    if let Some((minute, guards)) = matrix.most_contested_minute() {
+        text += &format!(
+            "Most contested minute: 00:{:02}, slept through by {} guards\n",
+            minute, guards
+        );
+    }
+    (text, matrix.to_csv())
+}
+
+pub fn day04(input_lines: &[Vec<String>]) -> (String, String) {
+    let naps_per_guard = parse_naps(input_lines);
 
     // A HashMap where the key is the ID a guard and the value is a the total time they spent
     // asleep while on shift.
     let mut total_minutes_asleep_per_guard = HashMap::<u32, u32>::new();
-    // A HashMap where the key is the ID a guard and the value is a vector of all the security
-    // events associated with that guard in chronological order.
+    // A HashMap where the key is the ID a guard and the value counts how many times they were
+    // asleep on each minute past midnight.
     let mut sleep_schedules_per_guard = HashMap::<u32, Counter<u32>>::new();
-    for (guard_id, security_events) in security_events_per_guard {
-        total_minutes_asleep_per_guard.insert(guard_id, count_minutes_asleep(&security_events));
-        sleep_schedules_per_guard.insert(guard_id, map_minutes_asleep(&security_events));
+    for (guard_id, naps) in naps_per_guard {
+        total_minutes_asleep_per_guard.insert(guard_id, count_minutes_asleep(&naps));
+        sleep_schedules_per_guard.insert(guard_id, map_minutes_asleep(&naps));
     }
 
     // Part 1
@@ -216,15 +494,51 @@ pub fn day04(input_lines: &[Vec<String>]) -> (String, String) {
     (format!("{}", answer1), format!("{}", answer2))
 }
 
+// Generate a shuffled log of `size` shifts for `cargo run scale 4`.
+// Every shift starts just after midnight and the guard sleeps between one and three times.
+pub fn generate(size: usize, rng: &mut StdRng) -> String {
+    let guard_ids: Vec<u32> = (0..size / 10 + 1)
+        .map(|_| rng.gen_range(1..=3000))
+        .collect();
+    let mut lines = Vec::new();
+    for shift in 0..size {
+        // Keep every date valid by pretending that each month has 28 days.
+        let date = format!(
+            "{}-{:02}-{:02}",
+            1518 + shift / (28 * 12),
+            1 + shift / 28 % 12,
+            1 + shift % 28
+        );
+        let start = rng.gen_range(0..5);
+        lines.push(format!(
+            "[{} 00:{:02}] Guard #{} begins shift",
+            date,
+            start,
+            guard_ids.choose(rng).unwrap()
+        ));
+        let sleep_count = rng.gen_range(1..=3);
+        let candidates: Vec<u32> = (start + 1..60).collect();
+        let mut minutes: Vec<u32> = candidates
+            .choose_multiple(rng, 2 * sleep_count)
+            .copied()
+            .collect();
+        minutes.sort_unstable();
+        for pair in minutes.chunks(2) {
+            lines.push(format!("[{} 00:{:02}] falls asleep", date, pair[0]));
+            lines.push(format!("[{} 00:{:02}] wakes up", date, pair[1]));
+        }
+    }
+    lines.shuffle(rng);
+    lines.join("\n")
+}
+
 #[cfg(test)]
 mod tests {
-    use super::day04;
+    use super::{day04, naps_per_guard, report, LogError, SecurityEvent, Timestamp};
     use crate::utils::load_input;
+    use shared::fuzz::assert_parser_never_panics;
 
-    #[test]
-    fn check_day04_case01() {
-        full_test(
-            "[1518-11-01 00:00] Guard #10 begins shift
+    const EXAMPLE: &str = "[1518-11-01 00:00] Guard #10 begins shift
 [1518-11-01 00:05] falls asleep
 [1518-11-01 00:25] wakes up
 [1518-11-01 00:30] falls asleep
@@ -240,12 +554,145 @@ mod tests {
 [1518-11-04 00:46] wakes up
 [1518-11-05 00:03] Guard #99 begins shift
 [1518-11-05 00:45] falls asleep
-[1518-11-05 00:55] wakes up", // INPUT STRING
-            "240",  // PART 1 RESULT
-            "4455", // PART 2 RESULT
+[1518-11-05 00:55] wakes up";
+
+    #[test]
+    fn check_day04_case01() {
+        full_test(
+            EXAMPLE, // INPUT STRING
+            "240",   // PART 1 RESULT
+            "4455",  // PART 2 RESULT
         )
     }
 
+    #[test]
+    fn check_day04_report() {
+        let (text, csv) = report(&load_input(EXAMPLE));
+        assert!(text.contains(
+            "     99 |                                    ----*****@****-----     | 30\n"
+        ));
+        assert!(text.contains("Sleepiest guards:\n  #10: 50 minutes\n  #99: 30 minutes\n"));
+        assert!(text
+            .contains("  #99: asleep at 00:45 on 3 shifts\n  #10: asleep at 00:24 on 2 shifts\n"));
+        assert!(text.ends_with("Most contested minute: 00:45, slept through by 2 guards\n"));
+
+        let rows: Vec<&str> = csv.lines().collect();
+        assert_eq!(rows.len(), 3);
+        assert!(rows[0].starts_with("guard,0,1,2,") && rows[0].ends_with(",58,59"));
+        assert!(rows[2].starts_with("99,0,0,") && rows[2].contains(",2,3,2,"));
+    }
+
+    fn log_error(lines: &[&str]) -> LogError {
+        let events = lines.iter().map(|line| line.parse().unwrap()).collect();
+        naps_per_guard(events).unwrap_err()
+    }
+
+    #[test]
+    fn check_day04_timestamps() {
+        let event: SecurityEvent = "[0999-02-28 23:58] Guard #7 begins shift".parse().unwrap();
+        assert_eq!(event.time.to_string(), "0999-02-28 23:58");
+        assert!("[1600-02-29 00:00] wakes up"
+            .parse::<SecurityEvent>()
+            .is_ok());
+
+        let error = "[1518-02-29 00:00] wakes up"
+            .parse::<SecurityEvent>()
+            .unwrap_err();
+        assert_eq!(
+            (error.column, error.found.as_str(), error.expected.as_str()),
+            (9, "29", "a day from 1 to 28")
+        );
+        let error = "[1518-11-01 00:60] wakes up"
+            .parse::<SecurityEvent>()
+            .unwrap_err();
+        assert_eq!((error.column, error.found.as_str()), (15, "60"));
+        let error = "[1518-11-01 00:05] dozes off"
+            .parse::<SecurityEvent>()
+            .unwrap_err();
+        assert_eq!((error.column, error.found.as_str()), (19, "dozes off"));
+    }
+
+    #[test]
+    fn check_day04_parser_never_panics() {
+        assert_parser_never_panics::<SecurityEvent>(&[
+            "[1518-11-01 23:58] Guard #99 begins shift",
+            "[1518-11-02 00:40] falls asleep",
+            "[1518-11-02 00:50] wakes up",
+        ]);
+    }
+
+    #[test]
+    fn check_day04_log_errors() {
+        let time = |day, hour, minute| Timestamp {
+            year: 1518,
+            month: 11,
+            day,
+            hour,
+            minute,
+        };
+        assert_eq!(
+            log_error(&["[1518-11-01 00:05] falls asleep"]),
+            LogError::NoGuardOnDuty(time(1, 0, 5))
+        );
+        assert_eq!(
+            log_error(&[
+                "[1518-11-01 23:50] falls asleep",
+                "[1518-11-01 23:45] Guard #10 begins shift",
+            ]),
+            LogError::OutsideMidnightHour(time(1, 23, 50))
+        );
+        assert_eq!(
+            log_error(&[
+                "[1518-11-01 00:00] Guard #10 begins shift",
+                "[1518-11-01 00:05] falls asleep",
+                "[1518-11-01 00:07] falls asleep",
+            ]),
+            LogError::AlreadyAsleep {
+                guard_id: 10,
+                time: time(1, 0, 7)
+            }
+        );
+        assert_eq!(
+            log_error(&[
+                "[1518-11-01 00:00] Guard #10 begins shift",
+                "[1518-11-01 00:05] wakes up",
+            ]),
+            LogError::AlreadyAwake {
+                guard_id: 10,
+                time: time(1, 0, 5)
+            }
+        );
+        assert_eq!(
+            log_error(&[
+                "[1518-11-01 00:00] Guard #10 begins shift",
+                "[1518-11-01 00:50] falls asleep",
+                "[1518-11-02 00:10] wakes up",
+            ]),
+            LogError::WokeOnAnotherDay {
+                guard_id: 10,
+                time: time(2, 0, 10)
+            }
+        );
+        assert_eq!(
+            log_error(&[
+                "[1518-11-01 00:00] Guard #10 begins shift",
+                "[1518-11-01 00:05] falls asleep",
+                "[1518-11-01 23:58] Guard #99 begins shift",
+            ]),
+            LogError::AsleepAtEndOfShift {
+                guard_id: 10,
+                time: time(1, 0, 5)
+            }
+        );
+        assert_eq!(
+            log_error(&[
+                "[1518-11-01 00:00] Guard #10 begins shift",
+                "[1518-11-01 00:00] Guard #99 begins shift",
+            ]),
+            LogError::DuplicateTime(time(1, 0, 0))
+        );
+    }
+
     fn full_test(input_text: &str, part1_result: &str, part2_result: &str) {
         let input_lines = load_input(input_text);
         assert_eq!(
@@ -1,4 +1,6 @@
 use itertools::Itertools;
+use rand::rngs::StdRng;
+use rand::Rng;
 use std::fmt;
 
 const ALPHABET: &str = "abcdefghijklmnopqrstuvwxyz";
@@ -9,17 +11,17 @@ struct PolymerUnit {
 }
 
 impl PolymerUnit {
-    fn _new(char: char) -> Self {
+    fn new(char: char) -> Self {
         Self { char }
     }
 
-    fn _reacts_with(&self, other: &Self) -> bool {
-        self.char.to_ascii_lowercase() == other.char.to_ascii_lowercase()
+    fn reacts_with(&self, other: &Self) -> bool {
+        self.char.eq_ignore_ascii_case(&other.char)
             && self.char.is_lowercase() != other.char.is_lowercase()
     }
 
-    fn _is_type(&self, char: char) -> bool {
-        self.char.to_ascii_lowercase() == char.to_ascii_lowercase()
+    fn is_type(&self, char: char) -> bool {
+        self.char.eq_ignore_ascii_case(&char)
     }
 }
 
@@ -29,11 +31,11 @@ impl fmt::Display for PolymerUnit {
     }
 }
 
-fn _react_polymer_chain(chain: &mut Vec<PolymerUnit>) -> usize {
+fn react_polymer_chain(chain: &mut Vec<PolymerUnit>) -> usize {
     let mut restart_idx: usize = 0;
     'outer: loop {
         for (idx, pair) in chain.windows(2).enumerate().skip(restart_idx) {
-            if pair[0]._reacts_with(&pair[1]) {
+            if pair[0].reacts_with(&pair[1]) {
                 chain.remove(idx);
                 chain.remove(idx);
                 if idx != 0 {
@@ -87,10 +89,48 @@ pub fn day05(input_lines: &[Vec<String>]) -> (String, String) {
     (format!("{:?}", answer1), format!("{}", answer2))
 }
 
+// React the polymer by removing pairs of units from a vector in place, rather than with a stack.
+pub fn day05_remove_in_place(input_lines: &[Vec<String>]) -> (String, String) {
+    let original_polymer_chain: Vec<PolymerUnit> =
+        input_lines[0][0].chars().map(PolymerUnit::new).collect();
+    let answer1 = react_polymer_chain(&mut original_polymer_chain.clone());
+
+    let answer2 = ALPHABET
+        .chars()
+        .map(|letter| {
+            react_polymer_chain(
+                &mut original_polymer_chain
+                    .iter()
+                    .filter(|unit| !unit.is_type(letter))
+                    .cloned()
+                    .collect(),
+            )
+        })
+        .min()
+        .unwrap();
+
+    (format!("{:?}", answer1), format!("{}", answer2))
+}
+
+// Generate a polymer of `size` units for `cargo run scale 5`.
+pub fn generate(size: usize, rng: &mut StdRng) -> String {
+    (0..size.max(1))
+        .map(|_| {
+            let unit = rng.gen_range('a'..='z');
+            if rng.gen() {
+                unit.to_ascii_uppercase()
+            } else {
+                unit
+            }
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
-    use super::day05;
+    use super::{day05, day05_remove_in_place};
     use crate::utils::load_input;
+    use shared::fuzz::{assert_for_all, random_strings, DEFAULT_CASES, DEFAULT_SEED};
 
     #[test]
     fn check_day05_case01() {
@@ -101,6 +141,29 @@ mod tests {
         )
     }
 
+    #[test]
+    fn check_day05_reaction_never_grows_polymer() {
+        let polymers = random_strings("aAbBcCzZ", 200, DEFAULT_SEED, DEFAULT_CASES)
+            .into_iter()
+            .filter(|polymer| !polymer.is_empty());
+        assert_for_all(polymers, |polymer| {
+            let (part1, part2) = day05(&load_input(polymer));
+            let part1 = part1.parse::<usize>().unwrap();
+            part1 <= polymer.len() && part2.parse::<usize>().unwrap() <= part1
+        });
+    }
+
+    #[test]
+    fn check_day05_implementations_agree() {
+        let polymers = random_strings("aAbBcCzZ", 100, DEFAULT_SEED, DEFAULT_CASES / 10)
+            .into_iter()
+            .filter(|polymer| !polymer.is_empty());
+        assert_for_all(polymers, |polymer| {
+            let input_lines = load_input(polymer);
+            day05(&input_lines) == day05_remove_in_place(&input_lines)
+        });
+    }
+
     fn full_test(input_text: &str, part1_result: &str, part2_result: &str) {
         let input_lines = load_input(input_text);
         assert_eq!(
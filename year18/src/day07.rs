@@ -1,149 +1,132 @@
 // Potential improvements:
 //
-use itertools::all;
-use regex::Regex;
-use std::collections::{BTreeMap, HashSet};
+use shared::graph::DiGraph;
+use shared::parse_error::{parse_lines, ParseError};
+use shared::parser::{Cursor, Field};
+use shared::schedule::{schedule, Schedule};
 
-fn secs(c: char) -> i32 {
-    c as i32 - 4
-}
+// The number of workers, and the time every step takes on top of its letter's position in the
+// alphabet. The example has 2 workers and no base time instead.
+const WORKERS: usize = 5;
+const BASE_SECS: u64 = 60;
 
-#[derive(Clone)]
-struct Graph {
-    edges: BTreeMap<char, HashSet<char>>,
-    queue: Vec<char>,
-}
+// The widest the Gantt chart in the report can be.
+const GANTT_WIDTH: usize = 100;
 
-impl Graph {
-    fn from_input(input_lines: &Vec<String>) -> Self {
-        let mut edges = BTreeMap::<char, HashSet<char>>::new();
-        let mut steps = HashSet::<char>::new();
-        let re = Regex::new(r"Step | must be finished before step | can begin.").unwrap();
-        for line in input_lines {
-            let chars: Vec<char> = re.replace_all(line, "").chars().collect();
-            let u: char = chars[0];
-            let v: char = chars[1];
-            steps.insert(u);
-            steps.insert(v);
-            let requirements = edges.entry(v).or_insert_with(HashSet::new);
-            requirements.insert(u);
-        }
-        for step in steps {
-            edges.entry(step).or_insert_with(HashSet::<char>::new);
-        }
-        Graph {
-            edges,
-            queue: Vec::new(),
-        }
-    }
+// A step is named by a capital letter, which also sets how long it takes.
+#[derive(Debug)]
+pub(crate) struct Step(char);
 
-    fn complete(&mut self, step: &char) {
-        self.edges.remove(step);
-        for prereqs in self.edges.values_mut() {
-            prereqs.remove(step);
+impl Field for Step {
+    fn parse_field(cursor: &mut Cursor) -> Result<Self, ParseError> {
+        let start = *cursor;
+        match cursor.char()? {
+            c if c.is_ascii_uppercase() => Ok(Step(c)),
+            _ => Err(start.error(1, "a capital letter")),
         }
-        println!("Completed: {}", step);
     }
+}
 
-    fn queue_completed_steps(&mut self, in_progress: &[Option<char>]) {
-        for (&step, prereqs) in self.edges.iter() {
-            if !self.queue.contains(&step)
-                && prereqs.is_empty()
-                && !in_progress.contains(&Some(step))
-            {
-                self.queue.push(step);
-                self.queue.sort_unstable();
-                self.queue.reverse();
-            }
-        }
-        println!("Queue: {}", String::from_iter(self.queue.clone()));
+shared::pattern! {
+    #[pattern = "Step {before} must be finished before step {after} can begin."]
+    #[derive(Debug)]
+    pub(crate) struct Instruction {
+        before: Step,
+        after: Step,
     }
 }
 
-impl std::fmt::Display for Graph {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for (step, prereqs) in self.edges.iter() {
-            writeln!(f, "{}  <-  {:?}", step, prereqs)?;
-        }
-        Ok(())
-    }
+// An edge from each step to every step that has to wait for it.
+fn parse_steps(input_lines: &[Vec<String>]) -> DiGraph<char> {
+    let instructions: Vec<Instruction> =
+        parse_lines(input_lines, 0).unwrap_or_else(|error| panic!("{}", error));
+    DiGraph::from_edges(
+        instructions
+            .into_iter()
+            .map(|instruction| (instruction.before.0, instruction.after.0)),
+    )
 }
 
-pub fn day07(input_lines: &[Vec<String>]) -> (String, String) {
-    let graph = Graph::from_input(&input_lines[0]);
-    let mut order1: Vec<char> = Vec::new();
-    let mut graph1 = graph.clone();
-    graph1.queue_completed_steps(&[]);
-    while let Some(step) = graph1.queue.pop() {
-        graph1.complete(&step);
-        graph1.queue_completed_steps(&[]);
-        order1.push(step);
-    }
-    let answer1 = String::from_iter(order1);
-
-    let mut graph2 = graph;
-    let mut workers = vec![0, 0, 0, 0, 0];
-    let mut tasks: Vec<Option<char>> = vec![None, None, None, None, None];
-    let mut counter = 0;
-    loop {
-        graph2.queue_completed_steps(&tasks);
-
-        for worker in 0..5 {
-            if workers[worker] == 0 && tasks[worker].is_some() {
-                graph2.complete(&tasks[worker].unwrap());
-                graph2.queue_completed_steps(&tasks);
-                tasks[worker] = None;
-            }
-        }
+// Hand the available steps, in alphabetical order, to idle workers. Step A takes 1 second on top
+// of `base_secs`, B takes 2, and so on.
+fn assembly(steps: &DiGraph<char>, workers: usize, base_secs: u64) -> Schedule<char> {
+    schedule(
+        steps.nodes().cloned(),
+        |step| steps.successors(step),
+        workers,
+        |&step| base_secs + (step as u64 - 'A' as u64 + 1),
+    )
+    .expect("The steps can't depend on each other in a loop.")
+}
 
-        for worker in 0..5 {
-            if workers[worker] == 0 && tasks[worker].is_none() {
-                if let Some(next_task) = graph2.queue.pop() {
-                    tasks[worker] = Some(next_task);
-                    workers[worker] = secs(next_task)
-                }
-            }
-        }
+fn parse_assembly(input_lines: &[Vec<String>]) -> (DiGraph<char>, Schedule<char>) {
+    let steps = parse_steps(input_lines);
+    let assembly = assembly(&steps, WORKERS, BASE_SECS);
+    (steps, assembly)
+}
 
-        for worker in workers.iter_mut() {
-            if worker != &mut 0 {
-                *worker -= 1;
-            }
-        }
+// Who works on which step when, as a Gantt chart and as CSV, for `cargo run report 7`.
+pub fn report(input_lines: &[Vec<String>]) -> (String, String) {
+    let (_, assembly) = parse_assembly(input_lines);
+    (assembly.gantt(GANTT_WIDTH), assembly.to_csv())
+}
 
-        println!("{:?}", workers);
-        println!("{:?}", tasks);
-        println!("{}", counter);
-        if graph2.queue.is_empty() && all(&tasks, |&task| task.is_none()) {
-            break;
-        } else {
-            counter += 1;
-        }
-    }
-    let answer2 = counter;
+pub fn day07(input_lines: &[Vec<String>]) -> (String, String) {
+    let (steps, assembly) = parse_assembly(input_lines);
+
+    let answer1: String = steps
+        .topological_sort()
+        .expect("The steps can't depend on each other in a loop.")
+        .into_iter()
+        .collect();
+
+    let answer2 = assembly.total_time();
     (answer1, format!("{}", answer2))
 }
 
 #[cfg(test)]
 mod tests {
-    use super::day07;
+    use super::{assembly, day07, parse_steps, Instruction};
     use crate::utils::load_input;
 
-    #[test]
-    fn check_day07_case01() {
-        full_test(
-            "Step C must be finished before step A can begin.
+    const EXAMPLE: &str = "Step C must be finished before step A can begin.
 Step C must be finished before step F can begin.
 Step A must be finished before step B can begin.
 Step A must be finished before step D can begin.
 Step B must be finished before step E can begin.
 Step D must be finished before step E can begin.
-Step F must be finished before step E can begin.", // INPUT STRING
+Step F must be finished before step E can begin.";
+
+    #[test]
+    fn check_day07_case01() {
+        full_test(
+            EXAMPLE,  // INPUT STRING
             "CABDFE", // PART 1 RESULT
-            "0",      // PART 2 RESULT
+            "253",    // PART 2 RESULT
         )
     }
 
+    #[test]
+    fn check_day07_example_workers() {
+        // The example's two workers, with no time on top of each step.
+        let steps = parse_steps(&load_input(EXAMPLE));
+        assert_eq!(assembly(&steps, 2, 0).total_time(), 15);
+    }
+
+    #[test]
+    fn check_day07_steps_are_capital_letters() {
+        let error = "Step 1 must be finished before step A can begin."
+            .parse::<Instruction>()
+            .unwrap_err();
+        assert_eq!(
+            (error.column, error.expected.as_str()),
+            (5, "a capital letter")
+        );
+        assert!("Step Z must be finished before step a can begin."
+            .parse::<Instruction>()
+            .is_err());
+    }
+
     fn full_test(input_text: &str, part1_result: &str, part2_result: &str) {
         let input_lines = load_input(input_text);
         assert_eq!(
@@ -1,7 +1,160 @@
-pub fn day08(input_lines: &[Vec<String>]) -> (String, String) {
-    let mut queue = input_lines[0][0]
-        .split(' ')
-        .map(|val| val.parse::<i32>().unwrap());
+use rand::rngs::StdRng;
+use rand::Rng;
+use shared::parse_error::ParseError;
+use shared::parser::{parse_line, Cursor};
+use std::fmt::{self, Write};
+use std::str::FromStr;
+
+// A node of the license tree.
+#[derive(Debug, Default, Eq, PartialEq)]
+struct Node {
+    children: Vec<Node>,
+    metadata: Vec<u32>,
+}
+
+// Take the descendants apart one at a time, as dropping them recursively would overflow the call
+// stack for a deep enough tree.
+impl Drop for Node {
+    fn drop(&mut self) {
+        let mut descendants = std::mem::take(&mut self.children);
+        while let Some(mut node) = descendants.pop() {
+            descendants.append(&mut node.children);
+        }
+    }
+}
+
+// A node's header, with the node filled in as its children and metadata are read.
+struct Partial {
+    children_left: usize,
+    metadata_count: usize,
+    node: Node,
+}
+
+impl Partial {
+    fn parse(cursor: &mut Cursor) -> Result<Self, ParseError> {
+        Ok(Partial {
+            children_left: cursor.integer()?,
+            metadata_count: cursor.integer()?,
+            node: Node::default(),
+        })
+    }
+}
+
+impl FromStr for Node {
+    type Err = ParseError;
+
+    // The tree is written depth first: each node is the number of children and the number of
+    // metadata entries, then its children, then its metadata. The nodes that have been started
+    // but not finished are kept on a stack rather than recursing, so deep trees can't overflow.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_line(s, |cursor| {
+            let mut stack = vec![Partial::parse(cursor)?];
+            loop {
+                let top = stack.last_mut().unwrap();
+                if top.children_left > 0 {
+                    top.children_left -= 1;
+                    stack.push(Partial::parse(cursor)?);
+                    continue;
+                }
+                let Partial {
+                    metadata_count,
+                    mut node,
+                    ..
+                } = stack.pop().unwrap();
+                node.metadata = (0..metadata_count)
+                    .map(|_| cursor.integer())
+                    .collect::<Result<_, _>>()?;
+                match stack.last_mut() {
+                    Some(parent) => parent.node.children.push(node),
+                    None => return Ok(node),
+                }
+            }
+        })
+    }
+}
+
+impl Node {
+    // Every node with its depth, in the order they're written in the input.
+    fn iter(&self) -> impl Iterator<Item = (usize, &Node)> {
+        let mut stack = vec![(0, self)];
+        std::iter::from_fn(move || {
+            let (depth, node) = stack.pop()?;
+            stack.extend(node.children.iter().rev().map(|child| (depth + 1, child)));
+            Some((depth, node))
+        })
+    }
+
+    // The value of every node, in the same order as `iter`. A node without children is worth the
+    // sum of its metadata. Otherwise each metadata entry picks a child, counting from 1, and the
+    // node is worth the sum of the values of the children picked. Entries that don't match a
+    // child count for nothing.
+    fn values(&self) -> Vec<u32> {
+        let mut values = Vec::new();
+        // The values of the finished children of the nodes being worked out, in order.
+        let mut child_values: Vec<u32> = Vec::new();
+        let mut stack = vec![(self, None)];
+        while let Some((node, idx)) = stack.pop() {
+            let Some(idx) = idx else {
+                values.push(0);
+                stack.push((node, Some(values.len() - 1)));
+                stack.extend(node.children.iter().rev().map(|child| (child, None)));
+                continue;
+            };
+            let picked = child_values.split_off(child_values.len() - node.children.len());
+            values[idx] = if node.children.is_empty() {
+                node.metadata.iter().sum()
+            } else {
+                node.metadata
+                    .iter()
+                    .filter_map(|&entry| picked.get((entry as usize).checked_sub(1)?))
+                    .sum()
+            };
+            child_values.push(values[idx]);
+        }
+        values
+    }
+
+    fn value(&self) -> u32 {
+        self.values()[0]
+    }
+}
+
+impl fmt::Display for Node {
+    // One line per node with its metadata and value, indented under its parent.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Each node to draw, with what goes in front of it and in front of its descendants.
+        let mut stack = vec![(self, String::new(), String::new())];
+        for value in self.values() {
+            let (node, prefix, child_prefix) = stack.pop().unwrap();
+            let metadata: Vec<String> = node.metadata.iter().map(u32::to_string).collect();
+            writeln!(f, "{}[{}] = {}", prefix, metadata.join(", "), value)?;
+            for (idx, child) in node.children.iter().enumerate().rev() {
+                let (branch, indent) = if idx + 1 == node.children.len() {
+                    ("└─ ", "   ")
+                } else {
+                    ("├─ ", "│  ")
+                };
+                stack.push((
+                    child,
+                    format!("{}{}", child_prefix, branch),
+                    format!("{}{}", child_prefix, indent),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn parse_tree(input_lines: &[Vec<String>]) -> Node {
+    input_lines[0][0]
+        .parse()
+        .unwrap_or_else(|error: ParseError| panic!("{}", error.at(0, 0)))
+}
+
+// Sum the metadata straight from the numbers, keeping a stack of the number of children still to
+// come and the number of metadata entries of each node that's been started.
+fn metadata_sum(input_line: &str) -> i32 {
+    let mut queue = input_line.split(' ').map(|val| val.parse::<i32>().unwrap());
 
     let mut stack = vec![(queue.next().unwrap(), queue.next().unwrap())];
     let mut total = 0;
@@ -22,26 +175,141 @@ pub fn day08(input_lines: &[Vec<String>]) -> (String, String) {
             stack.push((queue.next().unwrap(), queue.next().unwrap()));
         }
     }
+    total
+}
 
-    let answer1 = total;
-    let answer2 = 0;
+pub fn day08(input_lines: &[Vec<String>]) -> (String, String) {
+    let answer1 = metadata_sum(&input_lines[0][0]);
+    let answer2 = parse_tree(input_lines).value();
     (format!("{}", answer1), format!("{}", answer2))
 }
 
+// The tree drawn with each node's metadata and value, and as CSV with a row per node, for
+// `cargo run report 8`. Nodes are numbered in the order they're written in the input.
+pub fn report(input_lines: &[Vec<String>]) -> (String, String) {
+    let tree = parse_tree(input_lines);
+    let values = tree.values();
+    let mut csv = String::from("node,parent,depth,children,metadata,value\n");
+    // The number of the most recent node at each depth, which is the parent of the next node
+    // one level deeper.
+    let mut latest_at_depth: Vec<usize> = Vec::new();
+    for (idx, (depth, node)) in tree.iter().enumerate() {
+        latest_at_depth.truncate(depth);
+        let parent = latest_at_depth
+            .last()
+            .map_or(String::new(), |parent| parent.to_string());
+        latest_at_depth.push(idx);
+        let metadata: Vec<String> = node.metadata.iter().map(u32::to_string).collect();
+        writeln!(
+            csv,
+            "{},{},{},{},{},{}",
+            idx,
+            parent,
+            depth,
+            node.children.len(),
+            metadata.join(" "),
+            values[idx]
+        )
+        .unwrap();
+    }
+    (tree.to_string(), csv)
+}
+
+// Generate a license tree with `size` nodes for `cargo run scale 8`.
+pub fn generate(size: usize, rng: &mut StdRng) -> String {
+    // Attach every node after the root to a random earlier node.
+    let mut children: Vec<Vec<usize>> = vec![Vec::new(); size.max(1)];
+    for node in 1..children.len() {
+        let parent = rng.gen_range(0..node);
+        children[parent].push(node);
+    }
+
+    let metadata_counts: Vec<usize> = children.iter().map(|_| rng.gen_range(1..=5)).collect();
+
+    // Write the tree out depth first, without recursing.
+    let mut numbers = Vec::new();
+    let mut stack = vec![(0, false)];
+    while let Some((node, visited)) = stack.pop() {
+        if visited {
+            for _ in 0..metadata_counts[node] {
+                numbers.push(rng.gen_range(1..=9));
+            }
+        } else {
+            numbers.push(children[node].len());
+            numbers.push(metadata_counts[node]);
+            stack.push((node, true));
+            stack.extend(children[node].iter().rev().map(|&child| (child, false)));
+        }
+    }
+    numbers
+        .iter()
+        .map(|n| n.to_string())
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
 #[cfg(test)]
 mod tests {
-    use super::day08;
+    use super::{day08, metadata_sum, report, Node};
     use crate::utils::load_input;
+    use shared::fuzz::assert_parser_never_panics;
+
+    const EXAMPLE: &str = "2 3 0 3 10 11 12 1 1 0 1 99 2 1 1 2";
 
     #[test]
     fn check_day08_case01() {
         full_test(
-            "2 3 0 3 10 11 12 1 1 0 1 99 2 1 1 2", // INPUT STRING
-            "138",                                 // PART 1 RESULT
-            "0",                                   // PART 2 RESULT
+            EXAMPLE, // INPUT STRING
+            "138",   // PART 1 RESULT
+            "66",    // PART 2 RESULT
         )
     }
 
+    #[test]
+    fn check_day08_tree() {
+        let tree: Node = EXAMPLE.parse().unwrap();
+        assert_eq!(tree.values(), vec![66, 33, 0, 99]);
+        let total: u32 = tree.iter().flat_map(|(_, node)| &node.metadata).sum();
+        assert_eq!(total as i32, metadata_sum(EXAMPLE));
+        assert_eq!(
+            tree.to_string(),
+            "[1, 1, 2] = 66
+├─ [10, 11, 12] = 33
+└─ [2] = 0
+   └─ [99] = 99
+"
+        );
+
+        let (_, csv) = report(&load_input(EXAMPLE));
+        assert_eq!(
+            csv.lines().collect::<Vec<_>>(),
+            vec![
+                "node,parent,depth,children,metadata,value",
+                "0,,0,2,1 1 2,66",
+                "1,0,1,0,10 11 12,33",
+                "2,0,1,1,2,0",
+                "3,2,2,0,99,99",
+            ]
+        );
+    }
+
+    #[test]
+    fn check_day08_deep_tree() {
+        // A chain of nodes far deeper than the call stack could recurse.
+        let depth = 200_000;
+        let input = format!("{}0 1 5{}", "1 1 ".repeat(depth), " 1".repeat(depth));
+        let tree: Node = input.parse().unwrap();
+        assert_eq!(tree.iter().count(), depth + 1);
+        assert_eq!(tree.value(), 5);
+    }
+
+    #[test]
+    fn check_day08_parser_never_panics() {
+        assert_parser_never_panics::<Node>(&[EXAMPLE, "0 1 7"]);
+        let error = "1 1 0 2 5".parse::<Node>().unwrap_err();
+        assert_eq!((error.column, error.expected.as_str()), (9, "an integer"));
+    }
+
     fn full_test(input_text: &str, part1_result: &str, part2_result: &str) {
         let input_lines = load_input(input_text);
         assert_eq!(
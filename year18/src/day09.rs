@@ -1,60 +1,243 @@
 // Potential improvements:
 //
-use std::collections::VecDeque;
-use regex::Regex;
+use rand::rngs::StdRng;
+use rand::Rng;
+use shared::circular_list::CircularList;
+use shared::parser::Cursor;
+use std::cmp::Reverse;
+use std::fmt::Write;
 
+shared::pattern! {
+    #[pattern = "{players} players; last marble is worth {last_marble} points"]
+    pub(crate) struct Game {
+        players: usize,
+        last_marble: u64,
+    }
+}
 
-pub fn day09(input_lines: &[Vec<String>]) -> (String, String) {
-    let re = Regex::new(r"(\d+) players; last marble is worth (\d+) points").unwrap();
-    let caps = re.captures(&input_lines[0][0]).unwrap();
-    let player_count = caps.get(1).unwrap().as_str().parse::<i32>().unwrap();
-    let last_marble_value = caps.get(2).unwrap().as_str().parse::<u64>().unwrap() * 100;
-
-    let mut circle  = VecDeque::new();
-    let mut player_queue = VecDeque::new();
-    for _ in 0..player_count {
-        player_queue.push_back(0);
+// Every marble that's a multiple of `SPECIAL_MULTIPLE` is kept by the player, along with the
+// marble `REMOVAL_OFFSET` places anticlockwise.
+const SPECIAL_MULTIPLE: u64 = 23;
+const REMOVAL_OFFSET: isize = 7;
+
+// Part 2 plays on until a marble this many times more valuable.
+const PART2_FACTOR: u64 = 100;
+
+#[derive(Clone, Copy, Debug)]
+struct Rules {
+    players: usize,
+    last_marble: u64,
+    special_multiple: u64,
+    removal_offset: isize,
+}
+
+impl Rules {
+    fn new(players: usize, last_marble: u64) -> Self {
+        Rules::with_scoring(players, last_marble, SPECIAL_MULTIPLE, REMOVAL_OFFSET)
     }
-    circle.push_back(0);
-    circle.push_back(2);
-    circle.push_back(1);
-    for marble_value in 3..=last_marble_value {
-        let mut player = player_queue.pop_front().unwrap();
-
-        if marble_value % 23 == 0 {
-            player += marble_value;
-            circle.rotate_right(8);
-            player += circle.pop_back().unwrap();
-            circle.rotate_left(2);
 
+    fn with_scoring(
+        players: usize,
+        last_marble: u64,
+        special_multiple: u64,
+        removal_offset: isize,
+    ) -> Self {
+        assert!(players > 0, "A game needs at least one player.");
+        // With a multiple of 1, every marble scores and takes another with it, emptying the circle.
+        assert!(
+            special_multiple > 1,
+            "The special multiple must be at least 2, not {}.",
+            special_multiple
+        );
+        Rules {
+            players,
+            last_marble,
+            special_multiple,
+            removal_offset,
+        }
+    }
+}
+
+// A player scoring: on playing `marble` they also took `removed`, taking their score (counting
+// both) to `total`. Players are numbered from 1.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct Scoring {
+    marble: u64,
+    player: usize,
+    removed: u64,
+    total: u64,
+}
+
+// Play every marble up to the last, returning every player's final score and every time a player
+// scored, in order. Player 1 places marble 1.
+fn play(rules: Rules) -> (Vec<u64>, Vec<Scoring>) {
+    let mut scores = vec![0; rules.players];
+    let mut trace = Vec::new();
+    let mut circle = CircularList::with_capacity(rules.last_marble as usize + 1);
+    let mut current = circle.push_back(0);
+    for marble in 1..=rules.last_marble {
+        if marble % rules.special_multiple == 0 {
+            let removed = circle.offset(current, -rules.removal_offset);
+            current = circle.next(removed);
+            let removed = circle.remove(removed);
+            let player = (marble as usize - 1) % rules.players;
+            scores[player] += marble + removed;
+            trace.push(Scoring {
+                marble,
+                player: player + 1,
+                removed,
+                total: scores[player],
+            });
         } else {
-            circle.push_back(marble_value);
-            circle.rotate_left(1);
+            current = circle.insert_after(circle.next(current), marble);
         }
-        // println!("{:?}", circle);
-        
-        player_queue.push_back(player);
     }
+    (scores, trace)
+}
+
+fn high_score(rules: Rules) -> u64 {
+    let (scores, _) = play(rules);
+    scores.into_iter().max().unwrap()
+}
 
-    let answer1 = player_queue.iter().max().unwrap();
-    let answer2 = 0;
+fn parse_game(input_lines: &[Vec<String>]) -> Game {
+    let line = &input_lines[0][0];
+    let game: Game = line.parse().unwrap_or_else(|error| panic!("{}", error));
+    if game.players == 0 {
+        panic!("{}", Cursor::new(line).error(1, "at least one player"));
+    }
+    game
+}
+
+pub fn day09(input_lines: &[Vec<String>]) -> (String, String) {
+    let game = parse_game(input_lines);
+
+    let answer1 = high_score(Rules::new(game.players, game.last_marble));
+    let answer2 = high_score(Rules::new(game.players, game.last_marble * PART2_FACTOR));
     (format!("{}", answer1), format!("{}", answer2))
 }
 
+// Every player's final score in part 1, best first, and the trace of every time someone scored as
+// CSV, for `cargo run report 9`.
+pub fn report(input_lines: &[Vec<String>]) -> (String, String) {
+    let game = parse_game(input_lines);
+    let (scores, trace) = play(Rules::new(game.players, game.last_marble));
+
+    let mut ranking: Vec<(usize, u64)> = scores
+        .iter()
+        .enumerate()
+        .map(|(player, &score)| (player + 1, score))
+        .collect();
+    ranking.sort_by_key(|&(player, score)| (Reverse(score), player));
+    let mut text = String::new();
+    for (player, score) in ranking {
+        let times_scored = trace
+            .iter()
+            .filter(|scoring| scoring.player == player)
+            .count();
+        writeln!(
+            text,
+            "Player {}: {} points from {} scoring turns",
+            player, score, times_scored
+        )
+        .unwrap();
+    }
+
+    let mut csv = String::from("marble,player,removed,total\n");
+    for scoring in trace {
+        writeln!(
+            csv,
+            "{},{},{},{}",
+            scoring.marble, scoring.player, scoring.removed, scoring.total
+        )
+        .unwrap();
+    }
+    (text, csv)
+}
+
+// Generate a game where the last marble is worth `size` points for `cargo run scale 9`.
+pub fn generate(size: usize, rng: &mut StdRng) -> String {
+    format!(
+        "{} players; last marble is worth {} points",
+        rng.gen_range(10..=500),
+        size
+    )
+}
+
 #[cfg(test)]
 mod tests {
-    use super::day09;
+    use super::{day09, high_score, play, report, Rules, Scoring};
     use crate::utils::load_input;
 
     #[test]
     fn check_day09_case01() {
         full_test(
-            "9 players; last marble is worth 25 points",  // INPUT STRING
-            "3sf2", // PART 1 RESULT
-            "0", // PART 2 RESULT
+            "9 players; last marble is worth 25 points", // INPUT STRING
+            "32",                                        // PART 1 RESULT
+            "22563",                                     // PART 2 RESULT
         )
     }
 
+    #[test]
+    fn check_day09_high_scores() {
+        assert_eq!(high_score(Rules::new(10, 1618)), 8317);
+        assert_eq!(high_score(Rules::new(13, 7999)), 146373);
+        assert_eq!(high_score(Rules::new(17, 1104)), 2764);
+        assert_eq!(high_score(Rules::new(21, 6111)), 54718);
+        assert_eq!(high_score(Rules::new(30, 5807)), 37305);
+    }
+
+    #[test]
+    fn check_day09_other_rules() {
+        // Every 5th marble scores, along with the marble 2 places anticlockwise.
+        let (scores, trace) = play(Rules::with_scoring(3, 20, 5, 2));
+        assert_eq!(scores, vec![18, 46, 28]);
+        let scoring = |marble, player, removed, total| Scoring {
+            marble,
+            player,
+            removed,
+            total,
+        };
+        assert_eq!(
+            trace,
+            vec![
+                scoring(5, 2, 3, 8),
+                scoring(10, 1, 8, 18),
+                scoring(15, 3, 13, 28),
+                scoring(20, 2, 18, 46),
+            ]
+        );
+
+        assert_eq!(play(Rules::with_scoring(4, 30, 6, 3)).0, vec![0, 65, 0, 40]);
+    }
+
+    #[test]
+    #[should_panic(expected = "expected at least one player, found \"0\"")]
+    fn check_day09_no_players() {
+        day09(&load_input("0 players; last marble is worth 25 points"));
+    }
+
+    #[test]
+    #[should_panic(expected = "The special multiple must be at least 2, not 0.")]
+    fn check_day09_special_multiple_of_zero() {
+        Rules::with_scoring(3, 20, 0, 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "The special multiple must be at least 2, not 1.")]
+    fn check_day09_special_multiple_of_one() {
+        Rules::with_scoring(3, 20, 1, 2);
+    }
+
+    #[test]
+    fn check_day09_report() {
+        let (text, csv) = report(&load_input("9 players; last marble is worth 25 points"));
+        assert!(text.starts_with(
+            "Player 5: 32 points from 1 scoring turns\nPlayer 1: 0 points from 0 scoring turns\n"
+        ));
+        assert_eq!(csv, "marble,player,removed,total\n23,5,9,32\n");
+    }
+
     fn full_test(input_text: &str, part1_result: &str, part2_result: &str) {
         let input_lines = load_input(input_text);
         assert_eq!(
@@ -1,73 +1,73 @@
 // Potential improvements:
 //
-use log::error;
-use pixels::{Pixels, SurfaceTexture};
-use regex::Regex;
+use crate::visualize::Visualize;
+use rand::rngs::StdRng;
+use rand::Rng;
+use shared::grid::Grid;
+use shared::ocr::read_letters;
+use shared::parse_error::{parse_lines, ParseError};
+use shared::parser::parse_line;
+use shared::point::{BoundingBox, Point, Point2};
 use std::fmt;
-use std::num::ParseIntError;
 use std::str::FromStr;
-use winit::dpi::LogicalSize;
-use winit::event::{Event, VirtualKeyCode};
-use winit::event_loop::{ControlFlow, EventLoop};
-use winit::window::WindowBuilder;
-use winit_input_helper::WinitInputHelper;
-use rand::Rng;
-use nalgebra::{Vector2, Matrix2};
 
 const BACKGROUND: [u8; 4] = [0, 0, 100, 255];
-const MIRROR_X_AXIS: Matrix2<i32> = Matrix2::new(1,0,0,-1);
+const STAR: [u8; 4] = [255, 255, 255, 255];
 
 #[derive(Clone, Copy)]
-struct Star {
-    p: Vector2<i32>,
-    v: Vector2<i32>,
+pub(crate) struct Star {
+    p: Point2,
+    v: Point2,
     colour: [u8; 4],
 }
 
 impl Star {
-    fn new(p: Vector2<i32>, v: Vector2<i32>) -> Star {
-        let colour = [
-            rand::thread_rng().gen_range(100..=255),
-            rand::thread_rng().gen_range(100..=255),
-            rand::thread_rng().gen_range(100..=255),
-            255
+    fn new(p: Point2, v: Point2) -> Star {
+        Star { p, v, colour: STAR }
+    }
+
+    fn paint(&mut self, rng: &mut impl Rng) {
+        self.colour = [
+            rng.gen_range(100..=255),
+            rng.gen_range(100..=255),
+            rng.gen_range(100..=255),
+            255,
         ];
-        Star { p, v, colour }
     }
 
     fn update(&mut self, time: i32) {
-        self.p += self.v * time;
+        self.p += self.v * time as i64;
     }
 
     fn rewind(&mut self, time: i32) {
-        self.p -= self.v * time;
+        self.p -= self.v * time as i64;
     }
 }
 
 impl fmt::Display for Star {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "Position: {}, \tVelocity: {}", self.p, self.v)
+        write!(f, "Position: <{}>, \tVelocity: <{}>", self.p, self.v)
     }
 }
 
 impl FromStr for Star {
-    type Err = ParseIntError;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let re =
-            Regex::new(r"position=< ?(-?\d+),  ?(-?\d+)> velocity=< ?(-?\d+),  ?(-?\d+)>").unwrap();
-        let caps = re.captures(s).unwrap();
-
-        Ok(Star::new(
-            Vector2::new(
-                caps.get(1).unwrap().as_str().parse::<i32>().unwrap(),
-                caps.get(2).unwrap().as_str().parse::<i32>().unwrap(),
-            ),
-            Vector2::new(
-                caps.get(3).unwrap().as_str().parse::<i32>().unwrap(),
-                caps.get(4).unwrap().as_str().parse::<i32>().unwrap(),
-            ),
-        ))
+        // A line looks like "position=< 9,  1> velocity=< 0, -2>".
+        parse_line(s, |cursor| {
+            let mut vector = |prefix: &str| {
+                cursor.literal(prefix)?;
+                let x = cursor.signed_integer()?;
+                cursor.literal(",")?;
+                let y = cursor.signed_integer()?;
+                cursor.literal(">")?;
+                Ok(Point([x, y]))
+            };
+            let p = vector("position=<")?;
+            let v = vector(" velocity=<")?;
+            Ok(Star::new(p, v))
+        })
     }
 }
 
@@ -81,16 +81,12 @@ impl fmt::Display for NightSky {
         let mut s = String::new();
         for j in (0..self.height()).rev() {
             for i in 0..self.width() {
-                let p_prime = Vector2::new(
-                    i as i32,
-                    j as i32
-                );
-                let p = MIRROR_X_AXIS * p_prime + self.northeast();
+                let p = self.northeast() + Point([i as i64, -(j as i64)]);
                 let mut square = '.';
                 for star in &self.stars {
                     if p == star.p {
                         square = '#';
-                        break
+                        break;
                     }
                 }
                 s.push(square);
@@ -107,6 +103,11 @@ impl NightSky {
         NightSky { stars, time: 0 }
     }
 
+    // Give every star a random colour, for the animation.
+    fn paint(&mut self, rng: &mut impl Rng) {
+        self.stars.iter_mut().for_each(|star| star.paint(rng));
+    }
+
     fn update(&mut self, time: i32) {
         self.stars.iter_mut().for_each(|star| {
             star.update(time);
@@ -121,74 +122,69 @@ impl NightSky {
         self.time -= time;
     }
 
-    fn north(&self) -> i32 {
-        self.stars
-            .iter()
-            .map(|star| star.p.y)
-            .max()
-            .unwrap()
+    fn north(&self) -> i64 {
+        self.stars.iter().map(|star| star.p.y()).max().unwrap()
     }
 
-    fn east(&self) -> i32 {
-        self.stars
-            .iter()
-            .map(|star| star.p.x)
-            .min()
-            .unwrap()
+    fn east(&self) -> i64 {
+        self.stars.iter().map(|star| star.p.x()).min().unwrap()
     }
 
-    fn south(&self) -> i32 {
-        self.stars
-            .iter()
-            .map(|star| star.p.y)
-            .min()
-            .unwrap()
+    fn south(&self) -> i64 {
+        self.stars.iter().map(|star| star.p.y()).min().unwrap()
     }
 
-    fn west(&self) -> i32 {
-        self.stars
-            .iter()
-            .map(|star| star.p.x)
-            .max()
-            .unwrap()
+    fn west(&self) -> i64 {
+        self.stars.iter().map(|star| star.p.x()).max().unwrap()
     }
 
     fn height(&self) -> u32 {
-        self.north().abs_diff(self.south()) + 1
+        self.north().abs_diff(self.south()) as u32 + 1
     }
 
     fn width(&self) -> u32 {
-        self.east().abs_diff(self.west()) + 1
+        self.east().abs_diff(self.west()) as u32 + 1
+    }
+
+    fn northeast(&self) -> Point2 {
+        Point([self.east(), self.north()])
     }
 
-    fn northeast(&self) -> Vector2<i32> {
-        Vector2::new(self.east(), self.north())
+    // The sky as it's displayed, with `true` where there's a star.
+    fn to_grid(&self) -> Grid<bool> {
+        let mut grid = Grid::new(self.width() as usize, self.height() as usize, false);
+        for star in &self.stars {
+            grid[(
+                (star.p.x() - self.east()) as usize,
+                (star.p.y() - self.south()) as usize,
+            )] = true;
+        }
+        grid
     }
 
     // The `frame` is the key object here. It's a u8 (0-255) array of size 4 * width * height.
-    fn draw(&self, frame: &mut [u8], height: u32, width: u32, northeast: Vector2<i32>) {
+    fn draw(&self, frame: &mut [u8], height: u32, width: u32, northeast: Point2) {
         // Each pixels is 4 elements of the array—rbga format.
         for (i, pixel) in frame.chunks_exact_mut(4).enumerate() {
-            
             // The frame coordinate system, S', has the origin at the top left corner,
             // x increases from left to right and y increases from top to bottom.
-            let p_prime = Vector2::new(
-                i as i32 % width as i32,
-                height as i32 - 1 - i as i32 / width as i32
-            );
+            let p_prime = Point([
+                i as i64 % width as i64,
+                height as i64 - 1 - i as i64 / width as i64,
+            ]);
 
             // The sky coordinate system, S, has the origin somewhere in the middle.
             // The northeast-most point in the sky is at the top left.
             // x increases from left to right and y increases from bottom to top.
             // To convert the point p' to this coordinate system involves offsetting by
             // the northeast point and flipping about the X axis.
-            let p: Vector2<i32> = MIRROR_X_AXIS * p_prime + northeast;
+            let p = northeast + Point([p_prime.x(), -p_prime.y()]);
 
             let mut rgba = BACKGROUND;
             for star in &self.stars {
                 if star.p == p {
                     rgba = star.colour;
-                    break
+                    break;
                 }
             }
 
@@ -197,125 +193,125 @@ impl NightSky {
     }
 }
 
-pub fn day10(input_lines: &[Vec<String>]) -> (String, String) {
-    let stars: Vec<Star> = input_lines[0]
+// The time at which two stars moving apart fastest along an axis were level on it, which is
+// about when every star is closest together along that axis. `None` if every star moves at the
+// same speed along it.
+fn crossing_time(stars: &[Star], axis: usize) -> Option<f64> {
+    let slowest = stars.iter().min_by_key(|star| star.v[axis])?;
+    let fastest = stars.iter().max_by_key(|star| star.v[axis])?;
+    let closing_speed = fastest.v[axis] - slowest.v[axis];
+    (closing_speed != 0).then(|| (slowest.p[axis] - fastest.p[axis]) as f64 / closing_speed as f64)
+}
+
+// The area of the box around the stars after `time` seconds, or 0 if there are no stars.
+fn area_at(stars: &[Star], time: i32) -> i64 {
+    let positions: Vec<Point2> = stars
         .iter()
-        .map(|line| line.parse::<Star>().unwrap())
+        .map(|star| star.p + star.v * time as i64)
         .collect();
+    BoundingBox::from_points(&positions).map_or(0, |bounds| bounds.volume())
+}
 
-    let mut sky = NightSky::new(stars);
+// Find when the stars are closest together. The stars moving apart fastest give an estimate,
+// which a local search then moves to where the box around the stars is smallest.
+fn message_time(stars: &[Star]) -> i32 {
+    let estimates: Vec<f64> = (0..2)
+        .filter_map(|axis| crossing_time(stars, axis))
+        .collect();
+    let mut time = if estimates.is_empty() {
+        0
+    } else {
+        (estimates.iter().sum::<f64>() / estimates.len() as f64)
+            .round()
+            .max(0.0) as i32
+    };
+    while time > 0 && area_at(stars, time - 1) < area_at(stars, time) {
+        time -= 1;
+    }
+    while area_at(stars, time + 1) < area_at(stars, time) {
+        time += 1;
+    }
+    time
+}
 
-    // Minimize the height of the sky.
-    // This isn't guaranteed to work, but it does for my case.
-    // I implemented the interactive mode below so that a user could step forwards and backwards
-    // through the animation to find the right time.
-    // Without first reducing the height of the sky, however, the frame would have been too large.
-    let mut height = sky.height();
-    while sky.height() <= height {
-        height = sky.height();
-        sky.update(1);
-    }
-    sky.rewind(1);
-
-    // Set to `true` for interactive mode!
-    if false {
-        let height = sky.height();
-        let width = sky.width();
-        let northeast = sky.northeast();
-
-        let event_loop = EventLoop::new();
-        let mut input = WinitInputHelper::new();
-        let window = {
-            let size = LogicalSize::new(width as f64, height as f64);
-            WindowBuilder::new()
-                .with_title("Day 10")
-                .with_inner_size(size)
-                .with_min_inner_size(size)
-                .build(&event_loop)
-                .unwrap()
-        };
-
-        let mut pixels = {
-            let window_size = window.inner_size();
-            let surface_texture = SurfaceTexture::new(window_size.width, window_size.height, &window);
-            Pixels::new(width, height, surface_texture).unwrap()
-        };
-
-        let mut go = false;
-        let mut forward = true;
-        event_loop.run(move |event, _, control_flow| {
-            // Draw the current frame
-            if let Event::RedrawRequested(_) = event {
-                sky.draw(pixels.get_frame(), height, width, northeast);
-                if pixels
-                    .render()
-                    .map_err(|e| error!("pixels.render() failed: {}", e))
-                    .is_err()
-                {
-                    *control_flow = ControlFlow::Exit;
-                    return;
-                }
-            }
-            // Handle input events
-            if input.update(&event) {
-                // Close events
-                if input.key_pressed(VirtualKeyCode::Escape) || input.quit() {
-                    *control_flow = ControlFlow::Exit;
-                    return;
-                }
+// Move the stars to when they spell out the message.
+// The interactive mode (`cargo run 10 --visualize`) lets a user step forwards and backwards
+// through the animation from there.
+fn align_stars(input_lines: &[Vec<String>]) -> NightSky {
+    let stars: Vec<Star> = parse_lines(input_lines, 0).unwrap_or_else(|error| panic!("{}", error));
+    // The sky is drawn around the stars, so there must be some.
+    if stars.is_empty() {
+        panic!("{}", ParseError::new("", "", "at least one star").at(0, 0));
+    }
 
-                // Resize the window
-                if let Some(size) = input.window_resized() {
-                    pixels.resize_surface(size.width, size.height);
-                }
+    let time = message_time(&stars);
+    let mut sky = NightSky::new(stars);
+    sky.update(time);
+    sky
+}
 
-                if input.key_pressed(VirtualKeyCode::Up) {
-                    sky.update(1);
-                    println!("{}", sky.time);
-                }
+// A fixed window onto the night sky, so that every frame of the animation is the same size.
+struct SkyView {
+    sky: NightSky,
+    height: u32,
+    width: u32,
+    northeast: Point2,
+}
 
-                if input.key_held(VirtualKeyCode::Right) {
-                    sky.update(1);
-                }
+impl Visualize for SkyView {
+    fn frame_size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
 
-                if input.key_pressed(VirtualKeyCode::Down) {
-                    sky.rewind(1);
-                    println!("{}", sky.time);
-                }
+    fn time(&self) -> i32 {
+        self.sky.time
+    }
 
-                if input.key_held(VirtualKeyCode::Left) {
-                    sky.rewind(1);
-                }
+    fn draw(&self, frame: &mut [u8]) {
+        self.sky
+            .draw(frame, self.height, self.width, self.northeast);
+    }
 
-                if input.key_pressed(VirtualKeyCode::Space) {
-                    go = !go
-                }
+    fn step_forward(&mut self) {
+        self.sky.update(1);
+    }
 
-                if input.key_pressed(VirtualKeyCode::Tab) {
-                    forward = !forward
-                }
+    fn step_back(&mut self) {
+        self.sky.rewind(1);
+    }
 
-                if go {
-                    if forward {
-                        sky.update(1);
-                    } else {
-                        sky.rewind(1);
-                    }
-                }
-                window.request_redraw();
-            }
-        });
+    fn text(&self) -> String {
+        self.sky.to_string()
     }
+}
+
+pub fn simulation(input_lines: &[Vec<String>], rng: &mut StdRng) -> Box<dyn Visualize> {
+    let mut sky = align_stars(input_lines);
+    sky.paint(rng);
+    Box::new(SkyView {
+        height: sky.height(),
+        width: sky.width(),
+        northeast: sky.northeast(),
+        sky,
+    })
+}
+
+pub fn day10(input_lines: &[Vec<String>]) -> (String, String) {
+    let sky = align_stars(input_lines);
 
-    let answer1 = &sky;
+    // Fall back to drawing the message if the letters can't be read.
+    let answer1 = read_letters(&sky.to_grid()).unwrap_or_else(|| format!("\n{}", sky));
     let answer2 = &sky.time;
-    (format!("\n{}", answer1), format!("{}", answer2))
+    (answer1, format!("{}", answer2))
 }
 
 #[cfg(test)]
 mod tests {
-    use super::day10;
-    use crate::utils::load_input;
+    use super::{day10, simulation, Star};
+    use crate::utils::{load_input, DEFAULT_SEED};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use shared::fuzz::assert_parser_never_panics;
 
     #[test]
     fn check_day10_case01() {
@@ -365,6 +361,77 @@ position=<-3,  6> velocity=< 2, -1>", // INPUT STRING
         )
     }
 
+    #[test]
+    fn check_day10_reads_message() {
+        // "FX", to be spelled out after 5000 seconds by stars coming from all directions.
+        let message = [
+            "######..#....#",
+            "#.......#....#",
+            "#........#..#.",
+            "#........#..#.",
+            "#####.....##..",
+            "#.........##..",
+            "#........#..#.",
+            "#........#..#.",
+            "#.......#....#",
+            "#.......#....#",
+        ];
+        let input_text: Vec<String> = message
+            .iter()
+            .enumerate()
+            .flat_map(|(y, row)| {
+                row.char_indices()
+                    .filter(|&(_, c)| c == '#')
+                    .map(move |(x, _)| (x as i64, y as i64))
+            })
+            .enumerate()
+            .map(|(idx, (x, y))| {
+                let (vx, vy) = (idx as i64 % 7 - 3, idx as i64 % 5 - 2);
+                format!(
+                    "position=<{}, {}> velocity=<{}, {}>",
+                    x - 5000 * vx,
+                    y - 5000 * vy,
+                    vx,
+                    vy
+                )
+            })
+            .collect();
+        full_test(&input_text.join("\n"), "FX", "5000");
+    }
+
+    #[test]
+    #[should_panic(expected = "expected at least one star, found end of line")]
+    fn check_day10_no_stars() {
+        day10(&load_input(""));
+    }
+
+    #[test]
+    fn check_day10_parser_never_panics() {
+        assert_parser_never_panics::<Star>(&[
+            "position=< 9,  1> velocity=< 0,  2>",
+            "position=<-50310,  10306> velocity=< 5, -1>",
+        ]);
+    }
+
+    #[test]
+    fn check_day10_frames_are_reproducible() {
+        let input_lines = load_input(
+            "position=< 9,  1> velocity=< 0,  2>
+position=< 7,  0> velocity=<-1,  0>
+position=< 3, -2> velocity=<-1,  1>",
+        );
+        let frames: Vec<Vec<u8>> = (0..2)
+            .map(|_| {
+                let state = simulation(&input_lines, &mut StdRng::seed_from_u64(DEFAULT_SEED));
+                let (width, height) = state.frame_size();
+                let mut frame = vec![0; 4 * width as usize * height as usize];
+                state.draw(&mut frame);
+                frame
+            })
+            .collect();
+        assert_eq!(frames[0], frames[1]);
+    }
+
     fn full_test(input_text: &str, part1_result: &str, part2_result: &str) {
         let input_lines = load_input(input_text);
         assert_eq!(
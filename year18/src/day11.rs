@@ -1,58 +1,117 @@
 // Potential improvements:
 //
-use std::i32;
-use nalgebra::DMatrix;
+use rand::rngs::StdRng;
+use rand::Rng;
+use shared::summed_area::SummedAreaTable;
+use std::fmt::Write;
 
 const POWER_GRID_SIZE: usize = 300;
 
-pub fn day11(input_lines: &[Vec<String>]) -> (String, String) {
-    let serial_number = input_lines[0][0].parse::<i32>().unwrap();
-
-    // The question is 1-indexed, but the matrix is 0-indexed.
-    let power_grid = DMatrix::<i32>::from_fn(
-        POWER_GRID_SIZE, 
-        POWER_GRID_SIZE, 
-        |y, x| ((x as i32 + 11) * (y as i32 + 1) + serial_number) * (x as i32 + 11) / 100 % 10 - 5
-    );
-    
-    let mut max_power: i32 = i32::MIN;
-    let mut max_indices= (0 ,0);
-    let subgrid_size = 3;
-    for jj in 0..=POWER_GRID_SIZE-subgrid_size {
-        for ii in 0..=POWER_GRID_SIZE-subgrid_size {
-            let new_power = power_grid.slice((jj,ii),(subgrid_size,subgrid_size)).sum();
-            if new_power > max_power {
-                max_power = new_power;
-                max_indices = (ii+1, jj+1);
-            }
-        }
-    }
+// The square of a given size with the most power. `x` and `y` are 1-indexed, as in the question.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct Square {
+    x: usize,
+    y: usize,
+    size: usize,
+    power: i32,
+}
 
-    let answer1 = max_indices;
-
-    let mut max_power: i32 = i32::MIN;
-    let mut max_indices= (0 ,0);
-    let mut max_subgrid_size = 0;
-    for subgrid_size in 1..=POWER_GRID_SIZE {
-        for jj in 0..=POWER_GRID_SIZE-subgrid_size {
-            for ii in 0..=POWER_GRID_SIZE-subgrid_size {
-                let new_power = power_grid.slice((jj,ii),(subgrid_size,subgrid_size)).sum();
-                if new_power > max_power {
-                    max_power = new_power;
-                    max_indices = (ii+1, jj+1);
-                    max_subgrid_size = subgrid_size;
+fn power_grid(serial_number: i32, grid_size: usize) -> SummedAreaTable<i32> {
+    // The question is 1-indexed, but the grid is 0-indexed.
+    SummedAreaTable::from_fn(grid_size, grid_size, |x, y| {
+        ((x as i32 + 11) * (y as i32 + 1) + serial_number) * (x as i32 + 11) / 100 % 10 - 5
+    })
+}
+
+// The best square of every size from 1 up to the whole grid, in order of size. Ties go to the
+// square that comes first, reading top to bottom and then left to right.
+fn best_squares(power: &SummedAreaTable<i32>) -> Vec<Square> {
+    let grid_size = power.width();
+    (1..=grid_size)
+        .map(|size| {
+            let mut best = Square {
+                x: 1,
+                y: 1,
+                size,
+                power: i32::MIN,
+            };
+            for y in 0..=grid_size - size {
+                for x in 0..=grid_size - size {
+                    let square_power = power.sum(x, y, size, size);
+                    if square_power > best.power {
+                        best = Square {
+                            x: x + 1,
+                            y: y + 1,
+                            size,
+                            power: square_power,
+                        };
+                    }
                 }
             }
-        }
-    }
+            best
+        })
+        .collect()
+}
+
+// The best squares for the input, which is the serial number, optionally followed by the size
+// of the grid.
+fn parse_squares(input_lines: &[Vec<String>]) -> Vec<Square> {
+    let mut tokens = input_lines[0][0].split(' ');
+    let serial_number = tokens.next().unwrap().parse::<i32>().unwrap();
+    let grid_size = tokens
+        .next()
+        .map_or(POWER_GRID_SIZE, |size| size.parse::<usize>().unwrap());
+    best_squares(&power_grid(serial_number, grid_size))
+}
 
-    let answer2 = (max_indices, max_subgrid_size);
+pub fn day11(input_lines: &[Vec<String>]) -> (String, String) {
+    let squares = parse_squares(input_lines);
+
+    let answer1 = squares
+        .get(2)
+        .map(|square| (square.x, square.y))
+        .unwrap_or_default();
+    // Ties go to the smallest square, as `max_by_key` returns the last of equal elements.
+    let best = squares
+        .iter()
+        .rev()
+        .max_by_key(|square| square.power)
+        .unwrap();
+    let answer2 = ((best.x, best.y), best.size);
     (format!("{:?}", answer1), format!("{:?}", answer2))
 }
 
+// The best square of every size, with its power.
+pub fn report(input_lines: &[Vec<String>]) -> (String, String) {
+    let squares = parse_squares(input_lines);
+
+    let mut text = String::from("Best square of each size:\n");
+    let mut csv = String::from("size,x,y,power\n");
+    for square in &squares {
+        writeln!(
+            text,
+            "{:>3}x{:<3} at ({}, {}) with power {}",
+            square.size, square.size, square.x, square.y, square.power
+        )
+        .unwrap();
+        writeln!(
+            csv,
+            "{},{},{},{}",
+            square.size, square.x, square.y, square.power
+        )
+        .unwrap();
+    }
+    (text, csv)
+}
+
+// Generate a serial number and a `size` x `size` grid for `cargo run scale 11`.
+pub fn generate(size: usize, rng: &mut StdRng) -> String {
+    format!("{} {}", rng.gen_range(1..10000), size.max(3))
+}
+
 #[cfg(test)]
 mod tests {
-    use super::day11;
+    use super::{best_squares, day11, power_grid, report, Square};
     use crate::utils::load_input;
 
     #[test]
@@ -73,6 +132,26 @@ mod tests {
         )
     }
 
+    #[test]
+    fn check_day11_best_squares() {
+        let squares = best_squares(&power_grid(18, 300));
+        assert_eq!(squares.len(), 300);
+        let summary = |square: &Square| (square.x, square.y, square.size, square.power);
+        assert_eq!(summary(&squares[2]), (33, 45, 3, 29));
+        assert_eq!(summary(&squares[15]), (90, 269, 16, 113));
+    }
+
+    #[test]
+    fn check_day11_report() {
+        let (text, csv) = report(&load_input("18 4"));
+        assert_eq!(text.lines().count(), 5);
+        assert_eq!(csv.lines().count(), 5);
+        assert!(text.starts_with("Best square of each size:\n  1x1   at ("));
+        assert!(csv.starts_with("size,x,y,power\n1,"));
+        // The only 4x4 square is the whole grid.
+        assert!(csv.lines().last().unwrap().starts_with("4,1,1,"));
+    }
+
     fn full_test(input_text: &str, part1_result: &str, part2_result: &str) {
         let input_lines = load_input(input_text);
         assert_eq!(
@@ -1,8 +1,12 @@
 // Potential improvements:
 //
-use std::str::{FromStr, ParseBoolError};
+use std::str::FromStr;
 
+use crate::visualize::Visualize;
 use itertools::Itertools;
+use rand::rngs::StdRng;
+use shared::cycle::find_cycle;
+use shared::parse_error::{expect_prefix, parse_lines, split_once, ParseError};
 
 // RULE_SIZE must be odd.
 const RULE_SIZE: usize = 5;
@@ -10,9 +14,14 @@ const LEFT_BUFFER: usize = RULE_SIZE;
 const RIGHT_BUFFER: usize = RULE_SIZE * 20;
 const PLANT: char = '#';
 const NO_PLANT: char = '.';
+// The number of generations shown at once by the visualisation, newest at the bottom.
+const HISTORY_ROWS: usize = 100;
+const TERMINAL_HISTORY_ROWS: usize = 20;
+const PLANT_RGBA: [u8; 4] = [50, 205, 50, 255];
+const NO_PLANT_RGBA: [u8; 4] = [40, 26, 13, 255];
 
 #[derive(Clone, Debug)]
-struct SpreadingRule {
+pub(crate) struct SpreadingRule {
     chars: Vec<char>,
     output: char,
 }
@@ -27,34 +36,52 @@ impl SpreadingRule {
     }
 }
 
+// Check that `pots`, a slice of `input`, contains only plants and empty pots.
+fn check_pots(input: &str, pots: &str) -> Result<(), ParseError> {
+    match pots.char_indices().find(|&(_, c)| c != PLANT && c != NO_PLANT) {
+        Some((idx, c)) => Err(ParseError::new(
+            input,
+            &pots[idx..idx + c.len_utf8()],
+            format!("'{}' or '{}'", PLANT, NO_PLANT),
+        )),
+        None => Ok(()),
+    }
+}
+
 impl FromStr for SpreadingRule {
-    type Err = ParseBoolError;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let parts = s.split(" => ").collect::<Vec<&str>>();
+        let (chars, output) = split_once(s, s, " => ")?;
+        check_pots(s, chars)?;
+        check_pots(s, output)?;
+        if chars.len() != RULE_SIZE {
+            return Err(ParseError::new(s, chars, format!("{} pots", RULE_SIZE)));
+        }
+        if output.len() != 1 {
+            return Err(ParseError::new(s, output, "1 pot"));
+        }
         Ok(SpreadingRule {
-            chars: parts[0].chars().collect_vec(),
-            output: parts[1].chars().collect_vec()[0],
+            chars: chars.chars().collect_vec(),
+            output: output.chars().next().unwrap(),
         })
     }
 }
 
 #[derive(Clone)]
-struct Generation {
+pub(crate) struct Generation {
     plants: Vec<char>,
     zero_index: usize,
 }
 
 impl FromStr for Generation {
-    type Err = ParseBoolError;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let pots = expect_prefix(s, s, "initial state: ")?;
+        check_pots(s, pots)?;
         let mut plants = vec![NO_PLANT; LEFT_BUFFER];
-        plants.extend(s
-            .split("initial state: ")
-            .collect::<Vec<&str>>()[1]
-            .chars()
-        );
+        plants.extend(pots.chars());
         plants.extend_from_slice(&[NO_PLANT; RIGHT_BUFFER]);
         Ok(Generation { plants, zero_index: LEFT_BUFFER })
     }
@@ -78,7 +105,39 @@ impl Generation {
                 })
         );
         next_gen_plants.extend_from_slice(&[NO_PLANT; RULE_SIZE / 2]);
-        Generation { plants: next_gen_plants, zero_index: self.zero_index }
+        let mut next_gen = Generation { plants: next_gen_plants, zero_index: self.zero_index };
+        next_gen.pad();
+        next_gen
+    }
+
+    // Keep at least RULE_SIZE empty pots at either end, so that the plants can keep spreading
+    // in either direction without falling off the ends.
+    fn pad(&mut self) {
+        let left = self.plants.iter().take_while(|&&pot| pot == NO_PLANT).count();
+        if left < RULE_SIZE {
+            self.plants.splice(0..0, vec![NO_PLANT; RULE_SIZE - left]);
+            self.zero_index += RULE_SIZE - left;
+        }
+        let right = self.plants.iter().rev().take_while(|&&pot| pot == NO_PLANT).count();
+        if right < RULE_SIZE {
+            self.plants.extend(vec![NO_PLANT; RULE_SIZE - right]);
+        }
+    }
+
+    // The pots from the first plant to the last, wherever they are.
+    fn pattern(&self) -> String {
+        String::from_iter(&self.plants)
+            .trim_matches(NO_PLANT)
+            .to_string()
+    }
+
+    fn pot_number_sum(&self) -> i64 {
+        self.plants
+            .iter()
+            .enumerate()
+            .filter(|&(_, &pot)| pot == PLANT)
+            .map(|(idx, _)| idx as i64 - self.zero_index as i64)
+            .sum()
     }
 }
 
@@ -88,46 +147,96 @@ impl std::fmt::Debug for Generation {
     }
 }
 
+struct PlantHistory {
+    rules: Vec<SpreadingRule>,
+    generations: Vec<Generation>,
+}
+
+impl Visualize for PlantHistory {
+    fn frame_size(&self) -> (u32, u32) {
+        (self.generations[0].plants.len() as u32, HISTORY_ROWS as u32)
+    }
+
+    fn time(&self) -> i32 {
+        self.generations.len() as i32 - 1
+    }
+
+    fn draw(&self, frame: &mut [u8]) {
+        let width = self.generations[0].plants.len();
+        let zero_index = self.generations[0].zero_index;
+        let first_row = self.generations.len().saturating_sub(HISTORY_ROWS);
+        for (i, pixel) in frame.chunks_exact_mut(4).enumerate() {
+            // Generations grow as the plants spread, so line them up by pot number.
+            let pot = |generation: &Generation| {
+                generation.plants.get(generation.zero_index - zero_index + i % width).copied()
+            };
+            let rgba = match self.generations.get(first_row + i / width) {
+                Some(generation) if pot(generation) == Some(PLANT) => PLANT_RGBA,
+                _ => NO_PLANT_RGBA,
+            };
+            pixel.copy_from_slice(&rgba);
+        }
+    }
+
+    fn step_forward(&mut self) {
+        let next_gen = self.generations.last().unwrap().next_generation(&self.rules);
+        self.generations.push(next_gen);
+    }
+
+    fn step_back(&mut self) {
+        if self.generations.len() > 1 {
+            self.generations.pop();
+        }
+    }
+
+    fn text(&self) -> String {
+        let first_row = self.generations.len().saturating_sub(TERMINAL_HISTORY_ROWS);
+        self.generations[first_row..]
+            .iter()
+            .map(|generation| format!("{:?}\n", generation))
+            .collect()
+    }
+}
+
+// Nothing in the simulation is random, so the runner's generator isn't used.
+pub fn simulation(input_lines: &[Vec<String>], _rng: &mut StdRng) -> Box<dyn Visualize> {
+    let (initial_gen, rules) = parse_input(input_lines);
+    Box::new(PlantHistory {
+        rules,
+        generations: vec![initial_gen],
+    })
+}
+
+fn parse_input(input_lines: &[Vec<String>]) -> (Generation, Vec<SpreadingRule>) {
+    let initial_gen: Generation = input_lines[0][0]
+        .parse::<Generation>()
+        .unwrap_or_else(|error| panic!("{}", error.at(0, 0)));
+    let rules: Vec<SpreadingRule> =
+        parse_lines(input_lines, 1).unwrap_or_else(|error| panic!("{}", error));
+    (initial_gen, rules)
+}
 
 pub fn day12(input_lines: &[Vec<String>]) -> (String, String) {
-    let mut current_gen: Generation = input_lines[0][0].parse::<Generation>().expect("Could not parse first line of input into `Generation`.");
-    let rules: Vec<SpreadingRule> = input_lines[1]
-        .iter()
-        .map(|rule| rule.parse::<SpreadingRule>().expect("Could not parse rules."))
-        .collect();
-    let mut next_gen = current_gen.next_generation(&rules);
-    // println!("0000: {:?}", current_gen);
-
-    for _idx in 0..20 {
-        current_gen = next_gen;
-        next_gen = current_gen.next_generation(&rules);
-        // println!("{:04}: {:?}", idx+1, current_gen);
-    }
-
-    let answer1 = current_gen.plants.iter().enumerate().fold(0, |acc, (idx, plant)| 
-        acc + (idx as i32 - current_gen.zero_index as i32 ) * (plant == &PLANT) as i32
-    );
+    let (initial_gen, rules) = parse_input(input_lines);
 
-    // Is this cheating?
-    // 50_000_000_000 is enormous! I let it run for a bit to see if any patterns emerged.
-    // I noticed that it looked stable from generation 89 onwards. It looked something like:
-    // 89: ...#..#.##.##....#.........
-    // 90: ....#..#.##.##....#........
-    // 91: .....#..#.##.##....#.......
-    // 92: ......#..#.##.##....#......
-    // etc.
-    // So I just copied the output from generation 90 and added them up as if the plants were shifted to the right by 50 billion - 90.
-    let gen_90_no_left_buffer = "..................#..#..#..#..#..#..#....#..#....#....#..#....#..#..#..#..#..#..#....#....#..#..#..#..#..#....#..#..#....#..#....#..#....#..#..#..#..#..#..#..#..#..#..#..#....#..#..#..#....#..........";
-    let answer2 = gen_90_no_left_buffer.chars().enumerate().fold(0, |acc, (idx, plant)|
-        acc + (idx as i64 + 50_000_000_000 - 90) * (plant == '#') as i64
+    // Eventually the same pattern of plants repeats, shifting along the row each time, so the
+    // sum can be extrapolated from there.
+    let history = find_cycle(
+        initial_gen,
+        |generation| generation.next_generation(&rules),
+        Generation::pattern,
     );
+
+    let answer1 = history.extrapolate(20, Generation::pot_number_sum);
+    let answer2 = history.extrapolate(50_000_000_000, Generation::pot_number_sum);
     (format!("{}", answer1), format!("{}", answer2))
 }
 
     #[cfg(test)]
     mod tests {
-        use super::day12;
+        use super::{day12, Generation, SpreadingRule};
         use crate::utils::load_input;
+        use shared::fuzz::assert_parser_never_panics;
 
     #[test]
     fn check_day12_case01() {
@@ -149,10 +258,16 @@ pub fn day12(input_lines: &[Vec<String>]) -> (String, String) {
 ###.# => #
 ####. => #",  // INPUT STRING
             "325", // PART 1 RESULT
-            "0", // PART 2 RESULT
+            "999999999374", // PART 2 RESULT
         )
     }
 
+    #[test]
+    fn check_day12_parsers_never_panic() {
+        assert_parser_never_panics::<Generation>(&["initial state: #..#.#..##......###...###"]);
+        assert_parser_never_panics::<SpreadingRule>(&["...## => #", "####. => ."]);
+    }
+
     fn full_test(input_text: &str, part1_result: &str, part2_result: &str) {
         let input_lines = load_input(input_text);
         assert_eq!(
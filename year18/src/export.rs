@@ -0,0 +1,97 @@
+// Headless rendering of a simulation, for machines without a display.
+//
+// `cargo run <DAY> --export-frames <PATH> --from <T0> --to <T1>` writes one frame per time step
+// from T0 to T1 inclusive. If PATH ends in `.gif` the frames are written as a single animated
+// GIF, otherwise PATH is treated as a directory and each frame is written to
+// `<PATH>/<T>.png`, with T zero-padded to six digits (e.g. `000042.png`).
+use crate::visualize::Visualize;
+use std::fs::{self, File};
+use std::io::{self, BufWriter};
+use std::path::Path;
+
+// The delay between GIF frames, in hundredths of a second.
+const GIF_FRAME_DELAY: u16 = 10;
+// A value between 1 and 30, trading GIF encoding speed for colour quantisation quality.
+const GIF_QUANTISATION_SPEED: i32 = 10;
+
+pub fn export_frames(state: &mut dyn Visualize, path: &Path, from: i32, to: i32) -> io::Result<()> {
+    if from > to {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("--from ({}) must not be after --to ({}).", from, to),
+        ));
+    }
+    if !state.seek(from) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("The simulation can't reach time {}.", from),
+        ));
+    }
+
+    let is_gif = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("gif"));
+    if is_gif {
+        write_gif(state, path, to)
+    } else {
+        write_pngs(state, path, to)
+    }
+}
+
+fn new_frame(state: &dyn Visualize) -> Vec<u8> {
+    let (width, height) = state.frame_size();
+    vec![0; 4 * width as usize * height as usize]
+}
+
+fn write_pngs(state: &mut dyn Visualize, dir: &Path, to: i32) -> io::Result<()> {
+    fs::create_dir_all(dir)?;
+    let (width, height) = state.frame_size();
+    let mut frame = new_frame(state);
+    loop {
+        state.draw(&mut frame);
+        let file = File::create(dir.join(format!("{:06}.png", state.time())))?;
+        let mut encoder = png::Encoder::new(BufWriter::new(file), width, height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.write_header()?.write_image_data(&frame)?;
+
+        if !state.step_towards(to) {
+            return Ok(());
+        }
+    }
+}
+
+fn write_gif(state: &mut dyn Visualize, path: &Path, to: i32) -> io::Result<()> {
+    let (width, height) = state.frame_size();
+    let (gif_width, gif_height) = match (u16::try_from(width), u16::try_from(height)) {
+        (Ok(w), Ok(h)) => (w, h),
+        _ => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("A {}x{} frame is too large for a GIF.", width, height),
+            ))
+        }
+    };
+
+    let file = BufWriter::new(File::create(path)?);
+    let mut encoder = gif::Encoder::new(file, gif_width, gif_height, &[]).map_err(gif_error)?;
+    encoder
+        .set_repeat(gif::Repeat::Infinite)
+        .map_err(gif_error)?;
+    let mut frame = new_frame(state);
+    loop {
+        state.draw(&mut frame);
+        let mut gif_frame =
+            gif::Frame::from_rgba_speed(gif_width, gif_height, &mut frame, GIF_QUANTISATION_SPEED);
+        gif_frame.delay = GIF_FRAME_DELAY;
+        encoder.write_frame(&gif_frame).map_err(gif_error)?;
+
+        if !state.step_towards(to) {
+            return Ok(());
+        }
+    }
+}
+
+fn gif_error(e: gif::EncodingError) -> io::Error {
+    io::Error::other(e)
+}
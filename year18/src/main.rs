@@ -23,10 +23,23 @@ mod day22;
 mod day23;
 mod day24;
 mod day25;
+mod export;
+mod terminal;
 mod utils;
+mod visualize;
+mod window;
 
+use itertools::Itertools;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use shared::diff_impl;
+use shared::scale::{self, Generator};
 use std::env;
+use std::path::Path;
+use visualize::Visualize;
 
+// A solver is deterministic and isn't given a random number generator: the same input always gives
+// the same answers, whatever `--seed` is. Only simulations and input generators use randomness.
 type DayFunction = fn(&[Vec<String>]) -> (String, String);
 static DAY_FUNCTIONS: [DayFunction; 25] = [
     day01::day01,
@@ -56,21 +69,219 @@ static DAY_FUNCTIONS: [DayFunction; 25] = [
     day25::day25,
 ];
 
+// Alternative implementations of a day, compared against the default with `cargo run diff-impl`.
+static ALTERNATIVE_DAY_FUNCTIONS: [(usize, &str, DayFunction); 2] = [
+    (3, "pairwise", day03::day03_pairwise),
+    (5, "remove_in_place", day05::day05_remove_in_place),
+];
+
+// Every implementation of `day`, starting with the default one.
+fn implementations(day: usize) -> Vec<(&'static str, DayFunction)> {
+    let mut implementations = vec![("default", DAY_FUNCTIONS[day - 1])];
+    implementations.extend(
+        ALTERNATIVE_DAY_FUNCTIONS
+            .iter()
+            .filter(|&&(alternative_day, _, _)| alternative_day == day)
+            .map(|&(_, name, day_function)| (name, day_function)),
+    );
+    implementations
+}
+
+// The number of generated inputs, from the smallest sizes, that `diff-impl` compares on.
+const DIFF_IMPL_GENERATED_INPUTS: usize = 2;
+
+// A simulation is given the runner's random number generator for anything random, such as colours.
+type SimulationFunction = fn(&[Vec<String>], &mut StdRng) -> Box<dyn Visualize>;
+
+// Days that can be animated with `cargo run <DAY> --visualize`, `--export-frames` or `--terminal`.
+fn simulation(day: usize) -> Option<SimulationFunction> {
+    match day {
+        10 => Some(day10::simulation),
+        12 => Some(day12::simulation),
+        _ => None,
+    }
+}
+
+// A report on a day's input beyond the two answers: text to print and a table of CSV.
+type ReportFunction = fn(&[Vec<String>]) -> (String, String);
+
+// Days with a report for `cargo run report <DAY>`, which prints the text, or saves the CSV with
+// `--csv <PATH>`.
+fn report(day: usize) -> Option<ReportFunction> {
+    match day {
+        4 => Some(day04::report),
+        7 => Some(day07::report),
+        8 => Some(day08::report),
+        9 => Some(day09::report),
+        11 => Some(day11::report),
+        _ => None,
+    }
+}
+
+// Days with an input generator for `cargo run scale <DAY>`, and the input sizes to try.
+fn generator(day: usize) -> Option<(Generator, &'static [usize])> {
+    match day {
+        1 => Some((day01::generate, &[1_000, 10_000, 100_000])),
+        2 => Some((day02::generate, &[250, 1_000, 4_000, 16_000])),
+        3 => Some((day03::generate, &[100, 200, 400, 800, 1_600])),
+        4 => Some((day04::generate, &[100, 1_000, 10_000])),
+        5 => Some((day05::generate, &[1_000, 10_000, 100_000, 1_000_000])),
+        6 => Some((day06::generate, &[10, 20, 40, 80])),
+        8 => Some((day08::generate, &[1_000, 10_000, 100_000])),
+        9 => Some((day09::generate, &[1_000, 10_000, 100_000])),
+        11 => Some((day11::generate, &[10, 20, 40, 80, 160])),
+        _ => None,
+    }
+}
+
+// The flags that are followed by a value on the command line.
+const VALUE_FLAGS: [&str; 5] = ["--seed", "--export-frames", "--from", "--to", "--csv"];
+
+// The arguments after the program name that aren't flags or the values of flags.
+fn positional_args(args: &[String]) -> Vec<&str> {
+    let mut positional = Vec::new();
+    let mut args = args.iter().skip(1);
+    while let Some(arg) = args.next() {
+        if VALUE_FLAGS.contains(&arg.as_str()) {
+            args.next();
+        } else if !arg.starts_with("--") {
+            positional.push(arg.as_str());
+        }
+    }
+    positional
+}
+
+// The value following `flag` on the command line, if the flag was given.
+fn flag_value<'a>(args: &'a [String], flag: &str) -> Option<&'a str> {
+    args.iter()
+        .position(|arg| arg == flag)
+        .map(|idx| {
+            args.get(idx + 1)
+                .unwrap_or_else(|| panic!("Please provide a value after {}.", flag))
+                .as_str()
+        })
+}
+
+// The day number given on the command line, which must be one of the days in `DAY_FUNCTIONS`.
+fn day_number(arg: &str) -> usize {
+    let day = arg
+        .parse::<usize>()
+        .expect("Please provide the day number as an integer.");
+    if (day < 1) || (day > DAY_FUNCTIONS.len()) {
+        panic!("Invalid day specified.");
+    }
+    day
+}
+
 fn main() {
     let args: Vec<String> = env::args().collect();
+    let positional = positional_args(&args);
+    let seed = flag_value(&args, "--seed").map_or(utils::DEFAULT_SEED, |seed| {
+        seed.parse()
+            .expect("Please provide the seed as an integer.")
+    });
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    if positional.first() == Some(&"scale") {
+        let day = day_number(
+            positional
+                .get(1)
+                .expect("Please provide the day number as an integer."),
+        );
+        let (generate, sizes) = generator(day).expect("No input generator for this day.");
+        let samples = scale::measure(generate, sizes, &mut rng, |input| {
+            DAY_FUNCTIONS[day - 1](&utils::load_input(input));
+        });
+        println!("Day {}", day);
+        print!("{}", scale::table(&samples));
+        return;
+    }
+
+    if positional.first() == Some(&"report") {
+        let day = positional
+            .get(1)
+            .and_then(|day| day.parse::<usize>().ok())
+            .expect("Please provide the day number as an integer.");
+        let report_function = report(day).expect("No report for this day.");
+        let (text, csv) = report_function(&load_input(day));
+        match flag_value(&args, "--csv") {
+            Some(path) => std::fs::write(path, csv).expect("Failed to write the CSV file."),
+            None => print!("{}", text),
+        }
+        return;
+    }
+
+    if positional.first() == Some(&"diff-impl") {
+        let days: Vec<usize> = match positional.get(1) {
+            Some(day) => vec![day_number(day)],
+            None => ALTERNATIVE_DAY_FUNCTIONS
+                .iter()
+                .map(|&(day, _, _)| day)
+                .dedup()
+                .collect(),
+        };
+        let mut agree = true;
+        for day in days {
+            let implementations = implementations(day);
+            let mut comparisons = vec![diff_impl::compare(
+                "input",
+                &load_input(day),
+                &implementations,
+            )];
+            if let Some((generate, sizes)) = generator(day) {
+                for &size in sizes.iter().take(DIFF_IMPL_GENERATED_INPUTS) {
+                    let input_lines = utils::load_input(&generate(size, &mut rng));
+                    comparisons.push(diff_impl::compare(
+                        &format!("generated (size {})", size),
+                        &input_lines,
+                        &implementations,
+                    ));
+                }
+            }
+            agree &= comparisons.iter().all(diff_impl::Comparison::agrees);
+            println!("Day {}", day);
+            print!("{}", diff_impl::report(&comparisons));
+        }
+        if !agree {
+            std::process::exit(1);
+        }
+        return;
+    }
+
     let min_day: usize;
     let max_day: usize;
-    if args.len() < 2 {
+    if let Some(day) = positional.first() {
+        min_day = day_number(day);
+        max_day = min_day;
+    } else {
         min_day = 1;
         max_day = DAY_FUNCTIONS.len();
-    } else {
-        min_day = args[1]
-            .parse::<usize>()
-            .expect("Please provide the day number as an integer.");
-        if (min_day < 1) || (min_day > DAY_FUNCTIONS.len()) {
-            panic!("Invalid day specified.");
+    }
+
+    let export_path = flag_value(&args, "--export-frames");
+    let terminal = args.iter().any(|arg| arg == "--terminal");
+    if args.iter().any(|arg| arg == "--visualize") || export_path.is_some() || terminal {
+        let simulation_function = simulation(min_day).expect("No visualisation for this day.");
+        let input_lines: Vec<Vec<String>> = load_input(min_day);
+        let mut state = simulation_function(&input_lines, &mut rng);
+        let parse_time = |flag| {
+            flag_value(&args, flag).map(|time: &str| {
+                time.parse::<i32>()
+                    .unwrap_or_else(|_| panic!("Please provide {} as an integer.", flag))
+            })
+        };
+        let from = parse_time("--from").unwrap_or_else(|| state.time());
+        let to = parse_time("--to").unwrap_or(from);
+
+        if let Some(path) = export_path {
+            export::export_frames(state.as_mut(), Path::new(path), from, to)
+                .expect("Failed to export frames.");
+        } else if terminal {
+            terminal::run(state.as_mut(), from, to).expect("Failed to render to the terminal.");
+        } else {
+            window::view(&format!("Day {}", min_day), state);
         }
-        max_day = min_day;
+        return;
     }
 
     for day in min_day..=max_day {
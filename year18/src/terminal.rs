@@ -0,0 +1,105 @@
+// An ANSI terminal renderer, for quick looks at a simulation over SSH.
+//
+// `cargo run <DAY> --terminal` redraws the text rendering of a simulation in place.
+// When run from a TTY the simulation is stepped with the keyboard:
+//   Space          play / pause
+//   Right / l      step forwards
+//   Left / h       step backwards
+//   q / Escape     quit
+// Otherwise it plays from `--from` to `--to` and exits.
+use crate::visualize::Visualize;
+use crossterm::event::{self, Event, KeyCode, KeyModifiers};
+use crossterm::style::{Color, Print, PrintStyledContent, Stylize};
+use crossterm::terminal::{self, ClearType};
+use crossterm::{cursor, execute, queue};
+use std::io::{self, IsTerminal, Stdout, Write};
+use std::thread;
+use std::time::Duration;
+
+const FRAME_DELAY: Duration = Duration::from_millis(100);
+
+pub fn run(state: &mut dyn Visualize, from: i32, to: i32) -> io::Result<()> {
+    if !state.seek(from) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("The simulation can't reach time {}.", from),
+        ));
+    }
+
+    let mut stdout = io::stdout();
+    if io::stdin().is_terminal() && stdout.is_terminal() {
+        terminal::enable_raw_mode()?;
+        execute!(stdout, terminal::EnterAlternateScreen, cursor::Hide)?;
+        let result = interact(&mut stdout, state);
+        execute!(stdout, cursor::Show, terminal::LeaveAlternateScreen)?;
+        terminal::disable_raw_mode()?;
+        result
+    } else {
+        loop {
+            draw(&mut stdout, state)?;
+            if !state.step_towards(to) {
+                return writeln!(stdout);
+            }
+            thread::sleep(FRAME_DELAY);
+        }
+    }
+}
+
+fn interact(stdout: &mut Stdout, state: &mut dyn Visualize) -> io::Result<()> {
+    let mut playing = false;
+    loop {
+        draw(stdout, state)?;
+
+        // While playing, step forwards every frame unless a key is pressed in the meantime.
+        if playing && !event::poll(FRAME_DELAY)? {
+            state.step_forward();
+            continue;
+        }
+
+        if let Event::Key(key) = event::read()? {
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    return Ok(())
+                }
+                KeyCode::Char(' ') => playing = !playing,
+                KeyCode::Right | KeyCode::Char('l') => state.step_forward(),
+                KeyCode::Left | KeyCode::Char('h') => state.step_back(),
+                _ => {}
+            }
+        }
+    }
+}
+
+// Redraw the whole screen from the top left corner, cropping the text to fit the terminal.
+// Each line is positioned explicitly because raw mode doesn't return the cursor on a newline.
+fn draw(stdout: &mut Stdout, state: &dyn Visualize) -> io::Result<()> {
+    let (columns, rows) = terminal::size().unwrap_or((u16::MAX, u16::MAX));
+    // The terminal can report no size at all, e.g. in the middle of being resized.
+    if rows == 0 || columns == 0 {
+        return Ok(());
+    }
+    queue!(
+        stdout,
+        cursor::MoveTo(0, 0),
+        terminal::Clear(ClearType::All),
+        Print(format!("t = {}", state.time()))
+    )?;
+    for (row, line) in state
+        .text()
+        .lines()
+        .take((rows as usize).saturating_sub(1))
+        .enumerate()
+    {
+        queue!(stdout, cursor::MoveTo(0, row as u16 + 1))?;
+        for c in line.chars().take(columns as usize) {
+            let styled = match c {
+                '#' => c.with(Color::Green).bold(),
+                '.' => c.with(Color::DarkGrey),
+                _ => c.stylize(),
+            };
+            queue!(stdout, PrintStyledContent(styled))?;
+        }
+    }
+    stdout.flush()
+}
@@ -1,3 +1,9 @@
+// The seed of the runner's random number generator when `--seed` isn't given, and of any
+// randomness in tests. Solvers are deterministic; randomness in a simulation or input generator
+// must come from a generator passed to it, so that the same seed always gives the same frames and
+// inputs.
+pub const DEFAULT_SEED: u64 = 2018;
+
 pub fn load_input(whole_input: &str) -> Vec<Vec<String>> {
     let single_lines: Vec<String> = whole_input
         .lines()
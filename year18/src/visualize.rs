@@ -0,0 +1,53 @@
+// A generic interface for simulation-style days.
+//
+// A day opts in by implementing `Visualize` for its state and registering a `simulation`
+// function in `main.rs`. Run it in a window with `cargo run <DAY> --visualize` (see `window.rs`),
+// or render it without one using `--export-frames` (see `export.rs`) or `--terminal` (see
+// `terminal.rs`).
+pub trait Visualize {
+    // The (width, height) of every frame, in pixels. This must not change between steps.
+    fn frame_size(&self) -> (u32, u32);
+
+    // The current time step of the simulation.
+    fn time(&self) -> i32;
+
+    // Draw the current state into `frame`, a u8 (0-255) array of size 4 * width * height.
+    // Each pixel is 4 elements of the array—rgba format—starting from the top left corner.
+    fn draw(&self, frame: &mut [u8]);
+
+    fn step_forward(&mut self);
+
+    fn step_back(&mut self);
+
+    // A character rendering of the current state, for the terminal renderer in `terminal.rs`.
+    // A `#` marks something of interest and a `.` marks empty space.
+    fn text(&self) -> String;
+
+    // Step forwards or backwards until the simulation reaches `time`.
+    // Returns false if the simulation stops moving before it gets there.
+    fn seek(&mut self, time: i32) -> bool {
+        while self.time() != time {
+            let before = self.time();
+            if before < time {
+                self.step_forward();
+            } else {
+                self.step_back();
+            }
+            if self.time() == before {
+                return false;
+            }
+        }
+        true
+    }
+
+    // Step forwards once, unless `to` has already been reached.
+    // Returns false once `to` has been reached or the simulation stops moving.
+    fn step_towards(&mut self, to: i32) -> bool {
+        let before = self.time();
+        if before >= to {
+            return false;
+        }
+        self.step_forward();
+        self.time() != before
+    }
+}
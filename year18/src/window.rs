@@ -0,0 +1,153 @@
+// The interactive window for `cargo run <DAY> --visualize`.
+//
+// Key bindings:
+//   Space       play / pause
+//   Tab         reverse the direction of playback
+//   Up / Down   step forwards / backwards by one (and print the time)
+//   Right/Left  step forwards / backwards while held
+//   ] / [       double / halve the playback speed
+//   = / -       zoom in / out
+//   Escape      quit
+use crate::visualize::Visualize;
+use log::error;
+use pixels::{Pixels, SurfaceTexture};
+use winit::dpi::LogicalSize;
+use winit::event::{Event, VirtualKeyCode};
+use winit::event_loop::{ControlFlow, EventLoop};
+use winit::window::WindowBuilder;
+use winit_input_helper::WinitInputHelper;
+
+const MAX_SPEED: u32 = 1024;
+const MAX_ZOOM: u32 = 16;
+// The initial zoom is chosen so that the window is at least this many pixels across.
+const MIN_WINDOW_SIZE: u32 = 400;
+
+struct Playback {
+    playing: bool,
+    forward: bool,
+    speed: u32,
+    zoom: u32,
+}
+
+impl Playback {
+    fn new(width: u32, height: u32) -> Self {
+        Playback {
+            playing: false,
+            forward: true,
+            speed: 1,
+            zoom: (MIN_WINDOW_SIZE / width.max(height)).clamp(1, MAX_ZOOM),
+        }
+    }
+
+    fn window_size(&self, width: u32, height: u32) -> LogicalSize<f64> {
+        LogicalSize::new((width * self.zoom) as f64, (height * self.zoom) as f64)
+    }
+}
+
+pub fn view(title: &str, mut state: Box<dyn Visualize>) -> ! {
+    let (width, height) = state.frame_size();
+    let mut playback = Playback::new(width, height);
+
+    let event_loop = EventLoop::new();
+    let mut input = WinitInputHelper::new();
+    let window = WindowBuilder::new()
+        .with_title(title)
+        .with_inner_size(playback.window_size(width, height))
+        .with_min_inner_size(LogicalSize::new(width as f64, height as f64))
+        .build(&event_loop)
+        .unwrap();
+
+    let mut pixels = {
+        let window_size = window.inner_size();
+        let surface_texture = SurfaceTexture::new(window_size.width, window_size.height, &window);
+        Pixels::new(width, height, surface_texture).unwrap()
+    };
+
+    event_loop.run(move |event, _, control_flow| {
+        // Draw the current frame
+        if let Event::RedrawRequested(_) = event {
+            state.draw(pixels.get_frame());
+            if pixels
+                .render()
+                .map_err(|e| error!("pixels.render() failed: {}", e))
+                .is_err()
+            {
+                *control_flow = ControlFlow::Exit;
+                return;
+            }
+        }
+        // Handle input events
+        if input.update(&event) {
+            // Close events
+            if input.key_pressed(VirtualKeyCode::Escape) || input.quit() {
+                *control_flow = ControlFlow::Exit;
+                return;
+            }
+
+            // Resize the window
+            if let Some(size) = input.window_resized() {
+                pixels.resize_surface(size.width, size.height);
+            }
+
+            if input.key_pressed(VirtualKeyCode::Up) {
+                state.step_forward();
+                println!("{}", state.time());
+            }
+
+            if input.key_held(VirtualKeyCode::Right) {
+                state.step_forward();
+            }
+
+            if input.key_pressed(VirtualKeyCode::Down) {
+                state.step_back();
+                println!("{}", state.time());
+            }
+
+            if input.key_held(VirtualKeyCode::Left) {
+                state.step_back();
+            }
+
+            if input.key_pressed(VirtualKeyCode::Space) {
+                playback.playing = !playback.playing;
+                if !playback.playing {
+                    println!("{}", state.time());
+                }
+            }
+
+            if input.key_pressed(VirtualKeyCode::Tab) {
+                playback.forward = !playback.forward;
+            }
+
+            if input.key_pressed(VirtualKeyCode::RBracket) {
+                playback.speed = (playback.speed * 2).min(MAX_SPEED);
+                println!("Speed: {}x", playback.speed);
+            }
+
+            if input.key_pressed(VirtualKeyCode::LBracket) {
+                playback.speed = (playback.speed / 2).max(1);
+                println!("Speed: {}x", playback.speed);
+            }
+
+            if input.key_pressed(VirtualKeyCode::Equals) && playback.zoom < MAX_ZOOM {
+                playback.zoom += 1;
+                window.set_inner_size(playback.window_size(width, height));
+            }
+
+            if input.key_pressed(VirtualKeyCode::Minus) && playback.zoom > 1 {
+                playback.zoom -= 1;
+                window.set_inner_size(playback.window_size(width, height));
+            }
+
+            if playback.playing {
+                for _ in 0..playback.speed {
+                    if playback.forward {
+                        state.step_forward();
+                    } else {
+                        state.step_back();
+                    }
+                }
+            }
+            window.request_redraw();
+        }
+    });
+}
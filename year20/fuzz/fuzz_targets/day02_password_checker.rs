@@ -0,0 +1,11 @@
+#![no_main]
+#![allow(dead_code)]
+
+use libfuzzer_sys::fuzz_target;
+
+#[path = "../../src/day02.rs"]
+mod day02;
+
+fuzz_target!(|input: &str| {
+    let _ = input.parse::<day02::PasswordChecker>();
+});
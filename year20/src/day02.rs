@@ -3,7 +3,7 @@
 use counter::Counter;
 use once_cell::sync::OnceCell;
 use regex::Regex;
-use std::convert::Infallible;
+use shared::parse_error::{parse_lines, parse_token, ParseError};
 use std::str::FromStr;
 
 struct PasswordPolicy {
@@ -12,28 +12,35 @@ struct PasswordPolicy {
     d2: usize,
 }
 
-struct PasswordChecker {
+pub(crate) struct PasswordChecker {
     pw: String,
     policy: PasswordPolicy,
 }
 
 impl FromStr for PasswordChecker {
-    type Err = Infallible;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         static RE: OnceCell<Regex> = OnceCell::new();
 
-        RE.get_or_init(|| Regex::new(r"(\d+)-(\d+) ([a-z]): (\w+)").unwrap())
+        let cap = RE
+            .get_or_init(|| Regex::new(r"^(\d+)-(\d+) ([a-z]): (\w+)$").unwrap())
             .captures(s)
-            .map(|cap| {
-                Ok(PasswordChecker::new(
-                    cap[4].parse().unwrap(),
-                    cap[3].parse().unwrap(),
-                    cap[1].parse().unwrap(),
-                    cap[2].parse().unwrap(),
-                ))
-            })
-            .expect("Failed to match regular expression against input.")
+            .ok_or_else(|| ParseError::new(s, s, "\"<min>-<max> <letter>: <password>\""))?;
+        let position = |i: usize| {
+            let token = cap.get(i).unwrap().as_str();
+            match parse_token(s, token, "a position")? {
+                // Positions are 1-indexed.
+                0 => Err(ParseError::new(s, token, "a position of at least 1")),
+                d => Ok(d),
+            }
+        };
+        Ok(PasswordChecker::new(
+            cap[4].to_string(),
+            cap[3].chars().next().unwrap(),
+            position(1)?,
+            position(2)?,
+        ))
     }
 }
 
@@ -54,17 +61,20 @@ impl PasswordChecker {
             .contains(&self.pw.chars().collect::<Counter<char>>()[&self.policy.required_char])
     }
 
+    // A position past the end of the password doesn't hold the required character.
     fn validate_occurrence_positions(&self) -> bool {
-        (self.pw.chars().nth(self.policy.d1 - 1).unwrap() == self.policy.required_char)
-            ^ (self.pw.chars().nth(self.policy.d2 - 1).unwrap() == self.policy.required_char)
+        let holds_required_char =
+            |position: usize| self.pw.chars().nth(position - 1) == Some(self.policy.required_char);
+        holds_required_char(self.policy.d1) ^ holds_required_char(self.policy.d2)
     }
 }
 
 pub fn day02(input_lines: &[Vec<String>]) -> (String, String) {
-    let (answer1, answer2) = input_lines[0]
+    let checkers: Vec<PasswordChecker> =
+        parse_lines(input_lines, 0).unwrap_or_else(|error| panic!("{error}"));
+    let (answer1, answer2) = checkers
         .iter()
-        .fold((0, 0), |(mut acc1, mut acc2), line| {
-            let checker: PasswordChecker = line.parse().unwrap();
+        .fold((0, 0), |(mut acc1, mut acc2), checker| {
             if checker.validate_occurrence_count() {
                 acc1 += 1;
             };
@@ -78,8 +88,9 @@ pub fn day02(input_lines: &[Vec<String>]) -> (String, String) {
 
 #[cfg(test)]
 mod tests {
-    use super::day02;
+    use super::{day02, PasswordChecker};
     use crate::utils::load_input;
+    use shared::fuzz::assert_parser_never_panics;
 
     #[test]
     fn check_day02_case01() {
@@ -89,7 +100,34 @@ mod tests {
 2-9 c: ccccccccc", // INPUT STRING
             "2", // PART 1 RESULT
             "1", // PART 2 RESULT
-        )
+        );
+    }
+
+    #[test]
+    fn check_day02_positions_past_the_end() {
+        full_test(
+            "1-30 a: abc
+3-30 c: abc
+30-31 a: abc", // INPUT STRING
+            "1", // PART 1 RESULT
+            "2", // PART 2 RESULT
+        );
+    }
+
+    #[test]
+    fn check_day02_parser_never_panics() {
+        assert_parser_never_panics::<PasswordChecker>(&["1-3 a: abcde", "12-16 c: ccccccccc"]);
+    }
+
+    #[test]
+    fn check_day02_parse_error_points_at_position() {
+        let error = "0-3 a: abcde".parse::<PasswordChecker>().err().unwrap();
+        assert_eq!((error.column, error.found.as_str()), (0, "0"));
+        let error = "1-99999999999999999999 a: abcde"
+            .parse::<PasswordChecker>()
+            .err()
+            .unwrap();
+        assert_eq!((error.column, error.expected.as_str()), (2, "a position"));
     }
 
     fn full_test(input_text: &str, part1_result: &str, part2_result: &str) {
@@ -1,20 +1,31 @@
-use std::num::TryFromIntError;
+use shared::grid::Grid;
 
-fn count_trees_for_slope(v: (usize, usize), lines: &[String]) -> Result<u64, TryFromIntError> {
-    lines
-        .iter()
+// Count the trees hit going `v.0` right and `v.1` down at each step. The slope repeats to the
+// right forever.
+fn count_trees_for_slope(v: (usize, usize), slope: &Grid<bool>) -> u64 {
+    (0..slope.height())
         .step_by(v.1)
         .enumerate()
-        .filter(|(counter, line)| line.chars().cycle().nth(v.0 * counter).unwrap() == '#')
-        .count()
-        .try_into()
+        .filter(|&(counter, y)| *slope.get_wrapping((v.0 * counter) as isize, y as isize))
+        .count() as u64
 }
 
 pub fn day03(input_lines: &[Vec<String>]) -> (String, String) {
-    let answer1 = count_trees_for_slope((3, 1), &input_lines[0]).unwrap();
-    let answer2: u64 = vec![(1, 1), (3, 1), (5, 1), (7, 1), (1, 2)]
+    let slope = Grid::parse(
+        input_lines,
+        0,
+        |c| match c {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        },
+        "'#' or '.'",
+    )
+    .unwrap_or_else(|error| panic!("{}", error));
+    let answer1 = count_trees_for_slope((3, 1), &slope);
+    let answer2: u64 = [(1, 1), (3, 1), (5, 1), (7, 1), (1, 2)]
         .iter()
-        .map(|&v| count_trees_for_slope(v, &input_lines[0]).unwrap())
+        .map(|&v| count_trees_for_slope(v, &slope))
         .product();
     (format!("{}", answer1), format!("{}", answer2))
 }
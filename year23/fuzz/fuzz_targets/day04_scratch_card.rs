@@ -0,0 +1,11 @@
+#![no_main]
+#![allow(dead_code)]
+
+use libfuzzer_sys::fuzz_target;
+
+#[path = "../../src/day04.rs"]
+mod day04;
+
+fuzz_target!(|input: &str| {
+    let _ = input.parse::<day04::ScratchCard>();
+});
@@ -0,0 +1,11 @@
+#![no_main]
+#![allow(dead_code)]
+
+use libfuzzer_sys::fuzz_target;
+
+#[path = "../../src/day05.rs"]
+mod day05;
+
+fuzz_target!(|input: &str| {
+    let _ = input.parse::<day05::Mapping>();
+});
@@ -1,6 +1,8 @@
 // Potential improvements:
 //
 
+use shared::parse_error::{parse_lines, ParseError};
+use shared::parser::{parse_line, Cursor};
 use std::collections::HashMap;
 use std::fmt::Display;
 use std::hash::Hash;
@@ -27,31 +29,37 @@ impl Display for CubeColour {
     }
 }
 
-struct CubeGame {
+pub(crate) struct CubeGame {
     id: u32,
     colour_maxes: HashMap<CubeColour, u32>,
 }
 
+// Parse a cube count and colour like "3 blue".
+fn parse_cubes(cursor: &mut Cursor) -> Result<(u32, CubeColour), ParseError> {
+    let count = cursor.integer()?;
+    cursor.spaces();
+    let colour_start = *cursor;
+    let colour = match cursor.word()? {
+        "red" => CubeColour::Red,
+        "green" => CubeColour::Green,
+        "blue" => CubeColour::Blue,
+        other => return Err(colour_start.error(other.chars().count(), "a cube colour")),
+    };
+    Ok((count, colour))
+}
+
 impl FromStr for CubeGame {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         // Parse a string like "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green"
-        let mut id_and_game = s.split(':');
-        let id = id_and_game
-            .next()
-            .unwrap()
-            .split(' ')
-            .nth(1)
-            .unwrap()
-            .parse::<u32>()
-            .unwrap();
-
-        let rounds = id_and_game
-            .next()
-            .unwrap()
-            .split(';')
-            .collect::<Vec<&str>>();
+        let (id, rounds) = parse_line(s, |cursor| {
+            cursor.literal("Game ")?;
+            let id = cursor.integer()?;
+            cursor.literal(":")?;
+            let rounds = cursor.separated1(|round| round.separated1(parse_cubes, ", "), ";")?;
+            Ok((id, rounds))
+        })?;
 
         // Find the max count of each colour across all rounds
         let mut colour_maxes: HashMap<CubeColour, u32> = HashMap::from([
@@ -59,26 +67,9 @@ impl FromStr for CubeGame {
             (CubeColour::Green, 0),
             (CubeColour::Blue, 0),
         ]);
-        for round in rounds {
-            let mut cube_sample: HashMap<CubeColour, u32> = HashMap::new();
-            for cube in round.trim().split(", ") {
-                let mut count_and_colour = cube.split(' ');
-                let count = count_and_colour
-                    .next()
-                    .unwrap()
-                    .trim()
-                    .parse::<u32>()
-                    .unwrap();
-                let colour = match count_and_colour.next().unwrap().trim() {
-                    "red" => CubeColour::Red,
-                    "green" => CubeColour::Green,
-                    "blue" => CubeColour::Blue,
-                    _ => panic!("Unknown colour"),
-                };
-                cube_sample.insert(colour, count);
-                if &count > colour_maxes.get(&colour).unwrap() {
-                    colour_maxes.insert(colour, count);
-                }
+        for (count, colour) in rounds.into_iter().flatten() {
+            if &count > colour_maxes.get(&colour).unwrap() {
+                colour_maxes.insert(colour, count);
             }
         }
         Ok(CubeGame { id, colour_maxes })
@@ -94,10 +85,8 @@ impl CubeGame {
 }
 
 pub fn day02(input_lines: &[Vec<String>]) -> (String, String) {
-    let cube_games = input_lines[0]
-        .iter()
-        .map(|line| CubeGame::from_str(line).unwrap())
-        .collect::<Vec<CubeGame>>();
+    let cube_games: Vec<CubeGame> =
+        parse_lines(input_lines, 0).unwrap_or_else(|error| panic!("{}", error));
 
     let answer1: u32 = cube_games
         .iter()
@@ -115,8 +104,9 @@ pub fn day02(input_lines: &[Vec<String>]) -> (String, String) {
 
 #[cfg(test)]
 mod tests {
-    use super::day02;
+    use super::{day02, CubeGame};
     use crate::utils::load_input;
+    use shared::fuzz::assert_parser_never_panics;
 
     #[test]
     fn check_day02_case01() {
@@ -131,6 +121,14 @@ Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green", // INPUT STRING
         )
     }
 
+    #[test]
+    fn check_day02_parser_never_panics() {
+        assert_parser_never_panics::<CubeGame>(&[
+            "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green",
+            "Game 12: 1 blue",
+        ]);
+    }
+
     fn full_test(input_text: &str, part1_result: &str, part2_result: &str) {
         let input_lines = load_input(input_text);
         assert_eq!(
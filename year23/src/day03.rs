@@ -1,71 +1,52 @@
-use std::collections::HashMap;
-
-fn clamp<T: PartialOrd>(input: T, min: T, max: T) -> T {
-    if input < min {
-        min
-    } else if input > max {
-        max
-    } else {
-        input
-    }
-}
+use shared::grid::Grid;
+use std::collections::{HashMap, HashSet};
 
 pub fn day03(input_lines: &[Vec<String>]) -> (String, String) {
-    let height = input_lines[0].len();
+    let schematic = Grid::parse_chars(input_lines, 0).unwrap_or_else(|error| panic!("{}", error));
 
     let mut gear_map: HashMap<(usize, usize), Vec<u32>> = HashMap::new();
 
     let mut answer1: u32 = 0;
 
-    for (ii, row) in input_lines[0].iter().enumerate() {
-        let mut num_builder = Vec::new();
-        let mut start_col: usize = 0;
-        for (jj, cc) in row.char_indices() {
-            if cc.is_ascii_digit() {
-                if num_builder.is_empty() {
-                    start_col = jj;
-                }
-                num_builder.push(cc);
+    for y in 0..schematic.height() {
+        let row = schematic.row(y);
+        let mut x = 0;
+        while x < row.len() {
+            if !row[x].is_ascii_digit() {
+                x += 1;
+                continue;
+            }
+            let start_col = x;
+            while x < row.len() && row[x].is_ascii_digit() {
+                x += 1;
             }
+            let num = row[start_col..x]
+                .iter()
+                .collect::<String>()
+                .parse::<u32>()
+                .unwrap();
 
-            if !num_builder.is_empty() && (!cc.is_ascii_digit() || jj == row.len() - 1) {
-                let num = num_builder
-                    .iter()
-                    .collect::<String>()
-                    .parse::<u32>()
-                    .unwrap();
-                num_builder.clear();
-                let mut touches = false;
-                for yy in clamp(ii.saturating_sub(1), 0, height - 1)..=clamp(ii + 1, 0, height - 1)
-                {
-                    for xx in clamp(start_col.saturating_sub(1), 0, row.len() - 1)
-                        ..clamp(jj + 1, 0, row.len() - 1)
-                    {
-                        gear_map.entry((xx, yy)).or_default().push(num);
-                        let this_char = input_lines[0][yy].chars().nth(xx).unwrap();
-                        if this_char != '.' && !this_char.is_ascii_digit() {
-                            touches = true;
-                        }
-                    }
-                }
-                if touches {
-                    answer1 += num;
+            // The symbols touching any digit of the number.
+            let symbols: HashSet<(usize, usize)> = (start_col..x)
+                .flat_map(|xx| schematic.neighbours8(xx, y))
+                .filter(|&pos| schematic[pos] != '.' && !schematic[pos].is_ascii_digit())
+                .collect();
+            if !symbols.is_empty() {
+                answer1 += num;
+            }
+            for pos in symbols {
+                if schematic[pos] == '*' {
+                    gear_map.entry(pos).or_default().push(num);
                 }
             }
         }
     }
 
-    let mut answer2 = 0;
-    for (ii, jj) in input_lines[0].iter().enumerate().flat_map(|(ii, row)| {
-        row.char_indices()
-            .filter_map(move |(jj, cc)| if cc == '*' { Some((ii, jj)) } else { None })
-    }) {
-        if let Some(nums) = gear_map.get(&(jj, ii)) {
-            if nums.len() == 2 {
-                answer2 += nums[0] * nums[1];
-            }
-        }
-    }
+    let answer2: u32 = gear_map
+        .values()
+        .filter(|nums| nums.len() == 2)
+        .map(|nums| nums[0] * nums[1])
+        .sum();
 
     (format!("{}", answer1), format!("{}", answer2))
 }
@@ -1,44 +1,18 @@
 // Potential improvements:
 //
-use anyhow::Result;
+use shared::memo::Memoised;
+use shared::parse_error::parse_lines;
 use std::collections::{BTreeMap, HashSet};
-use std::str::FromStr;
-
-#[derive(Debug)]
-struct ScratchCard {
-    id: u32,
-    winners: HashSet<u32>,
-    numbers: Vec<u32>,
-}
-
-// impl FromStr for ScratchCard  where the string looks like: "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53"
-impl FromStr for ScratchCard {
-    type Err = ();
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut winners = HashSet::new();
-        let mut numbers = Vec::new();
-
-        let mut parts = s.split(": ");
-        let mut card_id = parts.next().unwrap().split(' ').filter(|s| s != &"");
-        let id = card_id.nth(1).unwrap().parse::<u32>().unwrap();
-
-        let mut card_numbers = parts.next().unwrap().split(" | ");
-        let card_winners = card_numbers.next().unwrap().split(' ').filter(|s| s != &"");
-        let card_numbers = card_numbers.next().unwrap().split(' ').filter(|s| s != &"");
-
-        for winner in card_winners {
-            winners.insert(winner.parse::<u32>().unwrap());
-        }
-        for number in card_numbers {
-            numbers.push(number.parse::<u32>().unwrap());
-        }
-
-        Ok(Self {
-            id,
-            winners,
-            numbers,
-        })
+use std::ops::RangeInclusive;
+
+// A line looks like: "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53"
+shared::pattern! {
+    #[pattern = "Card {id}: {winners} | {numbers}"]
+    #[derive(Debug)]
+    pub(crate) struct ScratchCard {
+        id: u32,
+        winners: HashSet<u32>,
+        numbers: Vec<u32>,
     }
 }
 
@@ -57,50 +31,35 @@ impl ScratchCard {
         }
     }
 
-    fn copies(&self) -> HashSet<u32> {
-        (self.id + 1..=self.id + self.win_count()).collect::<HashSet<u32>>()
-    }
-}
-
-fn num_copies(sum: &mut u32, copy_map: &BTreeMap<u32, HashSet<u32>>, id: u32) {
-    let copies = copy_map.get(&id).unwrap();
-    if !copies.is_empty() {
-        copies.iter().for_each(|c| {
-            *sum += 1;
-            num_copies(sum, copy_map, *c);
-        });
+    // The ids of the cards this card wins copies of.
+    fn copies(&self) -> RangeInclusive<u32> {
+        self.id + 1..=self.id + self.win_count()
     }
 }
 
 pub fn day04(input_lines: &[Vec<String>]) -> (String, String) {
-    let scratch_cards = input_lines[0]
-        .iter()
-        .map(|s| s.parse::<ScratchCard>().unwrap())
-        .collect::<Vec<ScratchCard>>();
+    let scratch_cards: Vec<ScratchCard> =
+        parse_lines(input_lines, 0).unwrap_or_else(|error| panic!("{}", error));
 
     let answer1 = scratch_cards
         .iter()
         .filter_map(|sc| sc.score())
         .sum::<u32>();
 
-    let copy_map = scratch_cards.iter().fold(BTreeMap::new(), |mut map, sc| {
-        map.insert(sc.id, sc.copies());
-        map
-    });
-
-    let mut answer2 = 0;
-    for id in 1..=copy_map.len() {
-        answer2 += 1;
-        num_copies(&mut answer2, &copy_map, id as u32)
-    }
+    // The number of cards a card ends up as, counting itself and everything its copies win.
+    let cards: BTreeMap<u32, &ScratchCard> = scratch_cards.iter().map(|sc| (sc.id, sc)).collect();
+    let mut card_count =
+        Memoised::new(|recurse, id: &u32| 1 + cards[id].copies().map(&mut *recurse).sum::<u32>());
+    let answer2 = cards.keys().map(|&id| card_count.call(id)).sum::<u32>();
 
     (format!("{}", answer1), format!("{}", answer2))
 }
 
 #[cfg(test)]
 mod tests {
-    use super::day04;
+    use super::{day04, ScratchCard};
     use crate::utils::load_input;
+    use shared::fuzz::assert_parser_never_panics;
 
     #[test]
     fn check_day04_case01() {
@@ -116,6 +75,22 @@ Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11", // INPUT STRING
         )
     }
 
+    #[test]
+    fn check_day04_parser_never_panics() {
+        assert_parser_never_panics::<ScratchCard>(&[
+            "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53",
+            "Card  12:  1 21 | 69 82",
+        ]);
+    }
+
+    #[test]
+    fn check_day04_parse_error_points_at_number() {
+        let error = "Card 1: 41 4x | 83".parse::<ScratchCard>().unwrap_err();
+        assert_eq!((error.column, error.found.as_str()), (12, "x |"));
+        let error = "Card 1: 41 48".parse::<ScratchCard>().unwrap_err();
+        assert_eq!((error.column, error.expected.as_str()), (13, "\" | \""));
+    }
+
     fn full_test(input_text: &str, part1_result: &str, part2_result: &str) {
         let input_lines = load_input(input_text);
         assert_eq!(
@@ -1,152 +1,151 @@
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use shared::interval::{Interval, IntervalSet, PiecewiseMap};
+use shared::parse_error::{parse_token, ParseError};
+use shared::parser::parse_line;
 use std::str::FromStr;
 
+const MAP_NAMES: [&str; 7] = [
+    "seed-to-soil",
+    "soil-to-fertilizer",
+    "fertilizer-to-water",
+    "water-to-light",
+    "light-to-temperature",
+    "temperature-to-humidity",
+    "humidity-to-location",
+];
+
 #[derive(Debug)]
-struct Mapping {
+pub(crate) struct Mapping {
     to: u64,
     from: u64,
     size: u64,
 }
 
 impl FromStr for Mapping {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut nums = s
-            .split_ascii_whitespace()
-            .map(|x| x.parse::<u64>().unwrap());
-        Ok(Mapping {
-            to: nums.next().unwrap(),
-            from: nums.next().unwrap(),
-            size: nums.next().unwrap(),
-        })
+        let tokens = s.split_ascii_whitespace().collect::<Vec<&str>>();
+        let nums = tokens
+            .iter()
+            .map(|token| parse_token(s, token, "a u64"))
+            .collect::<Result<Vec<u64>, ParseError>>()?;
+        match nums[..] {
+            // The mapped ranges must fit in a u64.
+            [to, from, size]
+                if to.checked_add(size).is_some() && from.checked_add(size).is_some() =>
+            {
+                Ok(Mapping { to, from, size })
+            }
+            [_, _, _] => Err(ParseError::new(s, tokens[2], "a range that fits in a u64")),
+            [_, _, _, ..] => Err(ParseError::new(s, tokens[3], "end of line")),
+            _ => Err(ParseError::new(s, &s[s.len()..], "3 numbers")),
+        }
     }
 }
 
-impl Mapping {
-    fn contains(&self, val: &u64) -> bool {
-        &self.from <= val && val < &(self.from + self.size)
-    }
+// Parse the mappings in `input_lines[block]`, skipping the line with the map's name.
+fn parse_map(input_lines: &[Vec<String>], block: usize) -> PiecewiseMap<u64> {
+    input_lines[block]
+        .iter()
+        .enumerate()
+        .skip(1)
+        .map(|(line, s)| s.parse::<Mapping>().map_err(|error| error.at(block, line)))
+        .map(|mapping| mapping.map(|m| (Interval::from_len(m.from, m.size), m.to)))
+        .collect::<Result<PiecewiseMap<u64>, ParseError>>()
+        .unwrap_or_else(|error| panic!("{}", error))
+}
 
-    fn map(&self, val: &u64) -> Option<u64> {
-        if self.contains(val) {
-            Some(self.to + val - self.from)
-        } else {
-            None
-        }
-    }
+// Parse the seeds on the first line, like "seeds: 79 14 55 13".
+fn parse_seeds(line: &str) -> Result<Vec<u64>, ParseError> {
+    parse_line(line, |cursor| {
+        cursor.literal("seeds:")?;
+        cursor.separated1(|cursor| cursor.integer::<u64>(), "")
+    })
 }
 
-fn follow_mapping(input: &u64, mappings: &[Mapping]) -> u64 {
-    for mapping in mappings {
-        if let Some(output) = mapping.map(input) {
-            // println!("{} -> {} via {:?}", input, output, mapping);
-            return output;
-        }
+// Pair up the seeds into ranges for part 2, each a start followed by a length.
+fn seed_ranges(line: &str, seeds: &[u64]) -> Result<IntervalSet<u64>, ParseError> {
+    if seeds.len() % 2 == 1 {
+        return Err(ParseError::new(
+            line,
+            &line[line.len()..],
+            "the length of the last range",
+        ));
     }
-    // println!("{} -> {}", input, input);
-    *input
+    let lengths = line.split_ascii_whitespace().skip(2).step_by(2);
+    seeds
+        .chunks_exact(2)
+        .zip(lengths)
+        .map(|(chunk, length)| match chunk[0].checked_add(chunk[1]) {
+            Some(_) => Ok(Interval::from_len(chunk[0], chunk[1])),
+            None => Err(ParseError::new(line, length, "a range that fits in a u64")),
+        })
+        .collect()
 }
 
 pub fn day05(input_lines: &[Vec<String>]) -> (String, String) {
-    let mut lines = input_lines.iter();
+    let line = &input_lines[0][0];
+    let seeds = parse_seeds(line)
+        .map_err(|error| error.at(0, 0))
+        .unwrap_or_else(|error| panic!("{}", error));
 
-    let seeds = lines.next().unwrap()[0]
-        .split_ascii_whitespace()
-        .skip(1)
-        .map(|x| x.parse::<u64>().unwrap())
-        .collect::<Vec<u64>>();
-
-    let seeds_to_soil = lines
-        .next()
-        .unwrap()
-        .iter()
-        .skip(1)
-        .map(|s| s.parse::<Mapping>().expect("Failed to parse Mapping"))
-        .collect::<Vec<Mapping>>();
-    let soil_to_fertilizer = lines
-        .next()
-        .unwrap()
-        .iter()
-        .skip(1)
-        .map(|s| s.parse::<Mapping>().expect("Failed to parse Mapping"))
-        .collect::<Vec<Mapping>>();
-    let fertilizer_to_water = lines
-        .next()
-        .unwrap()
-        .iter()
-        .skip(1)
-        .map(|s| s.parse::<Mapping>().expect("Failed to parse Mapping"))
-        .collect::<Vec<Mapping>>();
-    let water_to_light = lines
-        .next()
-        .unwrap()
-        .iter()
-        .skip(1)
-        .map(|s| s.parse::<Mapping>().expect("Failed to parse Mapping"))
-        .collect::<Vec<Mapping>>();
-    let light_to_temperature = lines
-        .next()
-        .unwrap()
-        .iter()
-        .skip(1)
-        .map(|s| s.parse::<Mapping>().expect("Failed to parse Mapping"))
-        .collect::<Vec<Mapping>>();
-    let temperature_to_humidity = lines
-        .next()
-        .unwrap()
-        .iter()
-        .skip(1)
-        .map(|s| s.parse::<Mapping>().expect("Failed to parse Mapping"))
-        .collect::<Vec<Mapping>>();
-    let humidity_to_location = lines
-        .next()
-        .unwrap()
-        .iter()
-        .skip(1)
-        .map(|s| s.parse::<Mapping>().expect("Failed to parse Mapping"))
-        .collect::<Vec<Mapping>>();
+    // Seed to soil, soil to fertilizer and so on, through to humidity to location.
+    let maps: Vec<PiecewiseMap<u64>> = (1..=MAP_NAMES.len())
+        .map(|block| parse_map(input_lines, block))
+        .collect();
 
     let answer1 = seeds
         .iter()
-        .map(|seed| {
-            let soil = follow_mapping(seed, &seeds_to_soil);
-            let fertilizer = follow_mapping(&soil, &soil_to_fertilizer);
-            let water = follow_mapping(&fertilizer, &fertilizer_to_water);
-            let light = follow_mapping(&water, &water_to_light);
-            let temperature = follow_mapping(&light, &light_to_temperature);
-            let humidity = follow_mapping(&temperature, &temperature_to_humidity);
-            follow_mapping(&humidity, &humidity_to_location)
-        })
+        .map(|&seed| maps.iter().fold(seed, |value, map| map.map(value)))
         .min()
         .unwrap();
-    let answer2 = seeds
-        .chunks(2)
-        .map(|chunk| {
-            let mut lowest_location = u64::MAX;
-            for seed in chunk[0]..chunk[0] + chunk[1] {
-                let soil = follow_mapping(&seed, &seeds_to_soil);
-                let fertilizer = follow_mapping(&soil, &soil_to_fertilizer);
-                let water = follow_mapping(&fertilizer, &fertilizer_to_water);
-                let light = follow_mapping(&water, &water_to_light);
-                let temperature = follow_mapping(&light, &light_to_temperature);
-                let humidity = follow_mapping(&temperature, &temperature_to_humidity);
-                let location = follow_mapping(&humidity, &humidity_to_location);
-                // println!();
-                if location < lowest_location {
-                    lowest_location = location;
-                }
-            }
-            lowest_location
-        })
+
+    // Map whole ranges of seeds at once, splitting them wherever a mapping starts or ends.
+    let seed_ranges = seed_ranges(line, &seeds)
+        .map_err(|error| error.at(0, 0))
+        .unwrap_or_else(|error| panic!("{}", error));
+    let answer2 = maps
+        .iter()
+        .fold(seed_ranges, |ranges, map| map.map_set(&ranges))
         .min()
         .unwrap();
     (format!("{}", answer1), format!("{}", answer2))
 }
 
+// Generate an almanac with `size` lines in each map for `cargo run scale 5`.
+// The source ranges within a map don't overlap, and the seed ranges have a fixed length.
+pub fn generate(size: usize, rng: &mut StdRng) -> String {
+    const STRIDE: u64 = 1_000;
+    const SEED_RANGE_LEN: u64 = 10_000;
+    let extent = size.max(1) as u64 * STRIDE;
+
+    let seeds = (0..2)
+        .flat_map(|_| [rng.gen_range(0..extent), SEED_RANGE_LEN])
+        .map(|n| n.to_string())
+        .collect::<Vec<String>>();
+    let mut blocks = vec![format!("seeds: {}", seeds.join(" "))];
+    for name in MAP_NAMES {
+        let mut lines = (0..size as u64)
+            .map(|i| {
+                let to = rng.gen_range(0..extent);
+                let size = rng.gen_range(1..=STRIDE);
+                format!("{} {} {}", to, i * STRIDE, size)
+            })
+            .collect::<Vec<String>>();
+        lines.shuffle(rng);
+        blocks.push(format!("{} map:\n{}", name, lines.join("\n")));
+    }
+    blocks.join("\n\n")
+}
+
 #[cfg(test)]
 mod tests {
-    use super::day05;
+    use super::{day05, parse_seeds, seed_ranges, Mapping};
     use crate::utils::load_input;
+    use shared::fuzz::assert_parser_never_panics;
 
     #[test]
     fn check_day05_case01() {
@@ -189,6 +188,46 @@ humidity-to-location map:
         )
     }
 
+    #[test]
+    fn check_day05_parser_never_panics() {
+        assert_parser_never_panics::<Mapping>(&["50 98 2", "0 15 37"]);
+    }
+
+    #[test]
+    fn check_day05_parse_error_points_at_number() {
+        let error = "50 98".parse::<Mapping>().unwrap_err();
+        assert_eq!((error.column, error.expected.as_str()), (5, "3 numbers"));
+        let error = "50 98 2 7".parse::<Mapping>().unwrap_err();
+        assert_eq!((error.column, error.found.as_str()), (8, "7"));
+        let error = "1 18446744073709551615 2".parse::<Mapping>().unwrap_err();
+        assert_eq!(error.found, "2");
+    }
+
+    #[test]
+    fn check_day05_seeds_parse_error() {
+        let error = parse_seeds("seeds: 79 x").unwrap_err();
+        assert_eq!((error.column, error.found.as_str()), (9, " x"));
+        let error = parse_seeds("seeds:").unwrap_err();
+        assert_eq!(error.expected, "an integer");
+    }
+
+    #[test]
+    fn check_day05_odd_number_of_seeds() {
+        let line = "seeds: 79 14 55";
+        let error = seed_ranges(line, &parse_seeds(line).unwrap()).unwrap_err();
+        assert_eq!(
+            (error.column, error.expected.as_str()),
+            (15, "the length of the last range")
+        );
+    }
+
+    #[test]
+    fn check_day05_seed_range_overflow() {
+        let line = "seeds: 79 14 18446744073709551615 1";
+        let error = seed_ranges(line, &parse_seeds(line).unwrap()).unwrap_err();
+        assert_eq!((error.column, error.found.as_str()), (34, "1"));
+    }
+
     fn full_test(input_text: &str, part1_result: &str, part2_result: &str) {
         let input_lines = load_input(input_text);
         assert_eq!(
@@ -25,8 +25,13 @@ mod day24;
 mod day25;
 mod utils;
 
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use shared::scale::{self, Generator};
 use std::env;
 
+// A solver is deterministic and isn't given a random number generator: the same input always gives
+// the same answers, whatever `--seed` is. Only the input generators use randomness.
 type DayFunction = fn(&[Vec<String>]) -> (String, String);
 static DAY_FUNCTIONS: [DayFunction; 25] = [
     day01::day01,
@@ -56,21 +61,75 @@ static DAY_FUNCTIONS: [DayFunction; 25] = [
     day25::day25,
 ];
 
+// Days with an input generator for `cargo run scale <DAY>`, and the input sizes to try.
+fn generator(day: usize) -> Option<(Generator, &'static [usize])> {
+    match day {
+        5 => Some((day05::generate, &[10, 100, 1_000, 10_000])),
+        _ => None,
+    }
+}
+
+// The flags that are followed by a value on the command line.
+const VALUE_FLAGS: [&str; 1] = ["--seed"];
+
+// The arguments after the program name that aren't flags or the values of flags.
+fn positional_args(args: &[String]) -> Vec<&str> {
+    let mut positional = Vec::new();
+    let mut args = args.iter().skip(1);
+    while let Some(arg) = args.next() {
+        if VALUE_FLAGS.contains(&arg.as_str()) {
+            args.next();
+        } else if !arg.starts_with("--") {
+            positional.push(arg.as_str());
+        }
+    }
+    positional
+}
+
+// The value following `flag` on the command line, if the flag was given.
+fn flag_value<'a>(args: &'a [String], flag: &str) -> Option<&'a str> {
+    args.iter().position(|arg| arg == flag).map(|idx| {
+        args.get(idx + 1)
+            .unwrap_or_else(|| panic!("Please provide a value after {}.", flag))
+            .as_str()
+    })
+}
+
 fn main() {
     let args: Vec<String> = env::args().collect();
+    let positional = positional_args(&args);
+    let seed = flag_value(&args, "--seed").map_or(utils::DEFAULT_SEED, |seed| {
+        seed.parse()
+            .expect("Please provide the seed as an integer.")
+    });
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    if positional.first() == Some(&"scale") {
+        let day = positional
+            .get(1)
+            .and_then(|day| day.parse::<usize>().ok())
+            .expect("Please provide the day number as an integer.");
+        let (generate, sizes) = generator(day).expect("No input generator for this day.");
+        let samples = scale::measure(generate, sizes, &mut rng, |input| {
+            DAY_FUNCTIONS[day - 1](&utils::load_input(input));
+        });
+        println!("Day {}", day);
+        print!("{}", scale::table(&samples));
+        return;
+    }
     let min_day: usize;
     let max_day: usize;
-    if args.len() < 2 {
-        min_day = 1;
-        max_day = DAY_FUNCTIONS.len();
-    } else {
-        min_day = args[1]
+    if let Some(day) = positional.first() {
+        min_day = day
             .parse::<usize>()
             .expect("Please provide the day number as an integer.");
         if (min_day < 1) || (min_day > DAY_FUNCTIONS.len()) {
             panic!("Invalid day specified.");
         }
         max_day = min_day;
+    } else {
+        min_day = 1;
+        max_day = DAY_FUNCTIONS.len();
     }
 
     for day in min_day..=max_day {
@@ -1,3 +1,7 @@
+// The seed of the runner's random number generator when `--seed` isn't given, and of any
+// randomness in tests. Solvers are deterministic; only the input generators use randomness.
+pub const DEFAULT_SEED: u64 = 2023;
+
 pub fn load_input(whole_input: &str) -> Vec<Vec<String>> {
     let single_lines: Vec<String> = whole_input
         .lines()